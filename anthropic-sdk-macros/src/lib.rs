@@ -0,0 +1,422 @@
+//! Procedural macros for `anthropic-sdk-rust`.
+//!
+//! Use these through the SDK's `macros` feature rather than depending on this
+//! crate directly; the generated code refers to paths in `anthropic_sdk`.
+//!
+//! - `#[derive(ToolInput)]` builds a tool definition from a struct: the name
+//!   from the struct name, the description from its doc comment, and the input
+//!   schema from the field types and field doc comments.
+//! - `#[tool]` on a function generates a unit struct implementing
+//!   `ToolFunction` and `DefinedTool`, which deserializes the input into the
+//!   function's parameters and calls it.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Expr, Fields, FnArg,
+    Ident, ItemFn, Lit, LitStr, Pat, ReturnType, Type,
+};
+
+/// Derive `ToolInput` for a struct with named fields.
+///
+/// Optional `#[tool(name = "...", description = "...")]` overrides the defaults:
+/// the snake_case struct name without an `Input` suffix, and the doc comment.
+/// `#[serde(rename)]`, `#[serde(rename_all)]`, `#[serde(default)]` and
+/// `#[serde(skip)]` are honoured; `Option` fields are not required.
+#[proc_macro_derive(ToolInput, attributes(tool))]
+pub fn derive_tool_input(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_derive(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Turn a function into a tool.
+///
+/// Generates a `{Name}Tool` unit struct implementing `ToolFunction` and
+/// `DefinedTool`. The tool is named after the function, described by its doc
+/// comment, and takes one property per parameter. Parameters are described
+/// in an `# Arguments` section of the doc comment:
+///
+/// ```text
+/// /// Get the current weather.
+/// ///
+/// /// # Arguments
+/// /// * `city` - City to look up
+/// ```
+///
+/// The function may be sync or async, and may return any `ToolOutput`, or a
+/// `Result` of one whose error converts into `Box<dyn Error + Send + Sync>`.
+/// Optional `#[tool(name = "...", description = "...")]` overrides the defaults.
+#[proc_macro_attribute]
+pub fn tool(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut overrides = ToolOverrides::default();
+    let parser = syn::meta::parser(|meta| overrides.parse(meta));
+    parse_macro_input!(args with parser);
+    let function = parse_macro_input!(item as ItemFn);
+    expand_tool(overrides, function).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+#[derive(Default)]
+struct ToolOverrides {
+    name: Option<String>,
+    description: Option<String>,
+}
+
+impl ToolOverrides {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse::<LitStr>()?.value());
+        } else if meta.path.is_ident("description") {
+            self.description = Some(meta.value()?.parse::<LitStr>()?.value());
+        } else {
+            return Err(meta.error("expected `name` or `description`"));
+        }
+        Ok(())
+    }
+
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut overrides = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("tool")) {
+            attr.parse_nested_meta(|meta| overrides.parse(meta))?;
+        }
+        Ok(overrides)
+    }
+}
+
+/// One property of the generated schema.
+struct Property {
+    name: String,
+    ty: Type,
+    description: Option<String>,
+    required: bool,
+}
+
+fn expand_derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new(input.span(), "ToolInput requires a struct with named fields")),
+        },
+        _ => return Err(syn::Error::new(input.span(), "ToolInput can only be derived for structs")),
+    };
+
+    let overrides = ToolOverrides::from_attrs(&input.attrs)?;
+    let container = SerdeAttrs::from_attrs(&input.attrs)?;
+
+    let mut properties = Vec::new();
+    for field in fields {
+        let serde = SerdeAttrs::from_attrs(&field.attrs)?;
+        if serde.skip {
+            continue;
+        }
+        if serde.flatten {
+            return Err(syn::Error::new(field.span(), "ToolInput does not support #[serde(flatten)]"));
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        let name = match serde.rename {
+            Some(rename) => rename,
+            None => apply_rename_all(&unraw(ident), container.rename_all.as_deref()),
+        };
+        properties.push(Property {
+            name,
+            ty: field.ty.clone(),
+            description: doc_string(&field.attrs),
+            required: !serde.default && !container.default && !is_option(&field.ty),
+        });
+    }
+
+    let ident = &input.ident;
+    let name = overrides.name.unwrap_or_else(|| {
+        let name = snake_case(&unraw(ident));
+        match name.strip_suffix("_input") {
+            Some(stripped) if !stripped.is_empty() => stripped.to_string(),
+            _ => name,
+        }
+    });
+    let description = overrides.description.or_else(|| doc_string(&input.attrs)).unwrap_or_default();
+    let body = schema_body(&name, &description, &properties);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::anthropic_sdk::tools::ToolInput for #ident #ty_generics #where_clause {
+            fn tool() -> ::anthropic_sdk::types::Tool {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_tool(overrides: ToolOverrides, function: ItemFn) -> syn::Result<TokenStream2> {
+    let sig = &function.sig;
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(sig.generics.span(), "#[tool] functions cannot be generic"));
+    }
+
+    let docs = doc_string(&function.attrs).unwrap_or_default();
+    let (description, argument_docs) = split_arguments_section(&docs);
+
+    let mut idents = Vec::new();
+    let mut properties = Vec::new();
+    for arg in &sig.inputs {
+        let FnArg::Typed(arg) = arg else {
+            return Err(syn::Error::new(arg.span(), "#[tool] cannot be used on methods"));
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            return Err(syn::Error::new(arg.pat.span(), "#[tool] parameters must be plain identifiers"));
+        };
+        let name = unraw(&pat.ident);
+        properties.push(Property {
+            description: argument_docs.iter().find(|(arg, _)| *arg == name).map(|(_, doc)| doc.clone()),
+            required: !is_option(&arg.ty),
+            ty: (*arg.ty).clone(),
+            name,
+        });
+        idents.push(pat.ident.clone());
+    }
+
+    let fn_ident = &sig.ident;
+    let vis = &function.vis;
+    let name = overrides.name.unwrap_or_else(|| unraw(fn_ident));
+    let description = overrides.description.unwrap_or(description);
+    let tool_ident = format_ident!("{}Tool", pascal_case(&unraw(fn_ident)), span = fn_ident.span());
+    let input_ident = format_ident!("__{}Input", tool_ident);
+    let types = properties.iter().map(|property| &property.ty);
+    let body = schema_body(&name, &description, &properties);
+
+    let mut call = quote! { #fn_ident(#(input.#idents),*) };
+    if sig.asyncness.is_some() {
+        call = quote! { #call.await };
+    }
+    if returns_result(&sig.output) {
+        call = quote! { #call? };
+    }
+    let tool_doc = format!("Tool generated by `#[tool]` from [`{}`].", unraw(fn_ident));
+
+    Ok(quote! {
+        #function
+
+        #[doc = #tool_doc]
+        #[derive(Debug, Clone, Copy, Default)]
+        #vis struct #tool_ident;
+
+        #[doc(hidden)]
+        #[derive(::anthropic_sdk::__private::serde::Deserialize)]
+        #[serde(crate = "::anthropic_sdk::__private::serde")]
+        struct #input_ident {
+            #(#idents: #types,)*
+        }
+
+        impl ::anthropic_sdk::tools::ToolInput for #input_ident {
+            fn tool() -> ::anthropic_sdk::types::Tool {
+                #body
+            }
+        }
+
+        #[::anthropic_sdk::__private::async_trait::async_trait]
+        impl ::anthropic_sdk::tools::ToolFunction for #tool_ident {
+            async fn execute(
+                &self,
+                input: ::anthropic_sdk::__private::serde_json::Value,
+            ) -> ::std::result::Result<
+                ::anthropic_sdk::types::ToolResult,
+                ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>,
+            > {
+                let input: #input_ident = ::anthropic_sdk::__private::serde_json::from_value(input)?;
+                let output = #call;
+                ::std::result::Result::Ok(::anthropic_sdk::tools::ToolOutput::into_tool_result(output))
+            }
+        }
+
+        impl ::anthropic_sdk::tools::DefinedTool for #tool_ident {
+            fn definition(&self) -> ::anthropic_sdk::types::Tool {
+                <#input_ident as ::anthropic_sdk::tools::ToolInput>::tool()
+            }
+        }
+    })
+}
+
+fn schema_body(name: &str, description: &str, properties: &[Property]) -> TokenStream2 {
+    let fields = properties.iter().map(|property| {
+        let Property { name, ty, description, required } = property;
+        let description = match description {
+            Some(description) => quote! { ::std::option::Option::Some(#description) },
+            None => quote! { ::std::option::Option::None },
+        };
+        quote! { schema.field::<#ty>(#name, #description, #required); }
+    });
+    quote! {
+        let mut schema = ::anthropic_sdk::__private::ToolSchemaBuilder::default();
+        #(#fields)*
+        schema.build(#name, #description)
+    }
+}
+
+/// The serde attributes that change the shape of the schema.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    rename_all: Option<String>,
+    default: bool,
+    skip: bool,
+    flatten: bool,
+}
+
+impl SerdeAttrs {
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut serde = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                let path = &meta.path;
+                if path.is_ident("rename") && meta.input.peek(syn::Token![=]) {
+                    serde.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    return Ok(());
+                }
+                if path.is_ident("rename_all") && meta.input.peek(syn::Token![=]) {
+                    serde.rename_all = Some(meta.value()?.parse::<LitStr>()?.value());
+                    return Ok(());
+                }
+                if path.is_ident("default") {
+                    serde.default = true;
+                } else if path.is_ident("skip") || path.is_ident("skip_deserializing") {
+                    serde.skip = true;
+                } else if path.is_ident("flatten") {
+                    serde.flatten = true;
+                }
+                // Consume the value of attributes that don't affect the schema.
+                if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<Expr>()?;
+                } else if meta.input.peek(syn::token::Paren) {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    content.parse::<TokenStream2>()?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(serde)
+    }
+}
+
+/// The doc comment of an item, with the leading space of each line removed.
+fn doc_string(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta.require_name_value().ok()?.value {
+            Expr::Lit(expr) => match &expr.lit {
+                Lit::Str(lit) => Some(lit.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).trim_end().to_string())
+        .collect();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// Split an `# Arguments` section out of a doc comment, returning the
+/// remaining description and each ``* `name` - description`` entry.
+fn split_arguments_section(docs: &str) -> (String, Vec<(String, String)>) {
+    let mut description = Vec::new();
+    let mut arguments = Vec::new();
+    let mut in_arguments = false;
+    for line in docs.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            in_arguments = trimmed.trim_start_matches('#').trim().eq_ignore_ascii_case("arguments");
+            if in_arguments {
+                continue;
+            }
+        }
+        if !in_arguments {
+            description.push(line);
+            continue;
+        }
+        let Some(entry) = trimmed.strip_prefix('*').or_else(|| trimmed.strip_prefix('-')) else {
+            continue;
+        };
+        let entry = entry.trim_start();
+        let Some(rest) = entry.strip_prefix('`') else {
+            continue;
+        };
+        let Some((name, doc)) = rest.split_once('`') else {
+            continue;
+        };
+        let doc = doc.trim_start().trim_start_matches(['-', ':']).trim();
+        arguments.push((name.to_string(), doc.to_string()));
+    }
+    (description.join("\n").trim().to_string(), arguments)
+}
+
+fn is_option(ty: &Type) -> bool {
+    last_segment_is(ty, "Option")
+}
+
+fn returns_result(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Type(_, ty) => last_segment_is(ty, "Result"),
+        ReturnType::Default => false,
+    }
+}
+
+fn last_segment_is(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| segment.ident == name),
+        _ => false,
+    }
+}
+
+fn unraw(ident: &Ident) -> String {
+    let name = ident.to_string();
+    name.strip_prefix("r#").map(str::to_string).unwrap_or(name)
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, ch) in name.char_indices() {
+        if ch.is_uppercase() {
+            if i > 0 && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.extend(ch.to_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Apply a `#[serde(rename_all)]` rule to a snake_case field name.
+fn apply_rename_all(field: &str, rule: Option<&str>) -> String {
+    match rule {
+        Some("lowercase") => field.to_lowercase(),
+        Some("UPPERCASE") => field.to_uppercase(),
+        Some("PascalCase") => pascal_case(field),
+        Some("camelCase") => {
+            let pascal = pascal_case(field);
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => pascal,
+            }
+        }
+        Some("SCREAMING_SNAKE_CASE") => field.to_uppercase(),
+        Some("kebab-case") => field.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => field.replace('_', "-").to_uppercase(),
+        _ => field.to_string(),
+    }
+}
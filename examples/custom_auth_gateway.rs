@@ -9,7 +9,7 @@ use serde_json::json;
 fn extract_text_from_content(content: &[ContentBlock]) -> String {
     content.iter()
         .filter_map(|block| match block {
-            ContentBlock::Text { text } => Some(text.as_str()),
+            ContentBlock::Text { text, .. } => Some(text.as_str()),
             _ => None,
         })
         .collect::<Vec<_>>()
@@ -7,7 +7,7 @@ use std::time::Duration;
 fn extract_text_from_content(content: &[ContentBlock]) -> String {
     content.iter()
         .filter_map(|block| match block {
-            ContentBlock::Text { text } => Some(text.as_str()),
+            ContentBlock::Text { text, .. } => Some(text.as_str()),
             _ => None,
         })
         .collect::<Vec<_>>()
@@ -37,6 +37,7 @@ impl ToolFunction for SimpleAnalyzerTool {
         Ok(anthropic_sdk::ToolResult {
             content: vec![anthropic_sdk::types::ContentBlock::Text {
                 text: result.to_string(),
+                citations: None,
             }],
         })
     }
@@ -82,7 +82,7 @@ async fn demonstrate_conversation() -> Result<()> {
     
     println!("   ✅ Multi-turn conversation created:");
     println!("      Total messages: {}", params.messages.len());
-    println!("      System prompt: {:?}", params.system.as_ref().map(|s| &s[..50]));
+    println!("      System prompt: {:?}", params.system.as_ref().map(|s| s.to_text().chars().take(50).collect::<String>()));
     println!("      Temperature: {:?}", params.temperature);
     
     for (i, msg) in params.messages.iter().enumerate() {
@@ -172,7 +172,7 @@ async fn simulate_batch_processing() -> Result<(), Box<dyn std::error::Error>> {
     ];
     
     for (i, status) in statuses.iter().enumerate() {
-        batch.processing_status = *status;
+        batch.processing_status = status.clone();
         batch.request_counts.completed = (i as u32 * 25).min(100);
         
         println!("   • {:?}: {}% complete", status, batch.completion_percentage());
@@ -275,7 +275,7 @@ fn create_mock_result(custom_id: &str, success: bool, content: &str) -> BatchRes
             id: format!("msg_{}", custom_id),
             type_: "message".to_string(),
             role: Role::Assistant,
-            content: vec![ContentBlock::Text { text: content.to_string() }],
+            content: vec![ContentBlock::Text { text: content.to_string(), citations: None }],
             model: "claude-3-5-sonnet-latest".to_string(),
             stop_reason: Some(StopReason::EndTurn),
             stop_sequence: None,
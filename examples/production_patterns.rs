@@ -389,7 +389,7 @@ impl DocumentAnalysisService {
                     .create_with_builder("claude-3-5-sonnet-latest", 2048)
                     .message(anthropic_sdk::types::Role::User, content)
                     .tools(tools)
-                    .tool_choice(ToolChoice::Auto)
+                    .tool_choice(ToolChoice::auto())
                     .temperature(0.3)
                     .send()
                     .await
@@ -1,7 +1,11 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 use dotenvy::dotenv;
 use crate::types::errors::{AnthropicError, Result};
 use crate::http::auth::AuthMethod;
+use crate::http::cassette::Cassette;
+use crate::http::transport::HttpTransport;
 
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
@@ -11,6 +15,9 @@ pub struct ClientConfig {
     pub max_retries: u32,
     pub log_level: LogLevel,
     pub auth_method: AuthMethod,
+    pub cassette: Option<Arc<Cassette>>,
+    pub transport: Option<Arc<dyn HttpTransport>>,
+    pub unix_socket: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +39,9 @@ impl ClientConfig {
             max_retries: 2,
             log_level: LogLevel::Warn,
             auth_method: AuthMethod::Anthropic,
+            cassette: None,
+            transport: None,
+            unix_socket: None,
         }
     }
     
@@ -106,6 +116,26 @@ impl ClientConfig {
         self
     }
     
+    /// Record or replay API interactions through a cassette
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(Arc::new(cassette));
+        self
+    }
+    
+    /// Send requests through a custom transport instead of reqwest
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+    
+    /// Send requests over a Unix domain socket
+    ///
+    /// The base URL still supplies the `Host` header and any path prefix.
+    pub fn with_unix_socket(mut self, path: impl AsRef<Path>) -> Self {
+        self.unix_socket = Some(path.as_ref().to_path_buf());
+        self
+    }
+    
     /// Configure for custom gateway (Bearer token + base URL)
     pub fn for_custom_gateway(mut self, base_url: impl Into<String>) -> Self {
         self.base_url = base_url.into();
@@ -127,9 +157,26 @@ impl ClientConfig {
             });
         }
         
-        if !self.base_url.starts_with("http://") && !self.base_url.starts_with("https://") {
+        if let Some(path) = self.base_url.strip_prefix("unix://") {
+            if path.is_empty() {
+                return Err(AnthropicError::Configuration {
+                    message: "Unix socket base URL must include a socket path".to_string(),
+                });
+            }
+            if cfg!(not(unix)) {
+                return Err(AnthropicError::Configuration {
+                    message: "Unix sockets are not supported on this platform".to_string(),
+                });
+            }
+        } else if !self.base_url.starts_with("http://") && !self.base_url.starts_with("https://") {
+            return Err(AnthropicError::Configuration {
+                message: "Base URL must start with http://, https:// or unix://".to_string(),
+            });
+        }
+        
+        if self.unix_socket.is_some() && cfg!(not(unix)) {
             return Err(AnthropicError::Configuration {
-                message: "Base URL must start with http:// or https://".to_string(),
+                message: "Unix sockets are not supported on this platform".to_string(),
             });
         }
         
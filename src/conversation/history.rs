@@ -0,0 +1,520 @@
+//! Keeping conversation history within the context window.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::fmt;
+
+use crate::client::Anthropic;
+use crate::types::{
+    ContentBlockParam, MessageContent, MessageCreateParams, MessageParam, Result, Role, SystemPrompt,
+};
+
+/// Tokens charged for an image by the local estimate (roughly a 1.2 megapixel image)
+const IMAGE_TOKEN_ESTIMATE: u32 = 1600;
+
+/// How a [`HistoryPolicy`] measures the size of a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenMeasure {
+    /// Estimate locally with [`estimate_tokens`]; free but approximate
+    #[default]
+    Estimate,
+    /// Ask the API through `count_tokens`; exact but costs a request per measurement
+    CountTokens,
+}
+
+/// Roughly estimate the input tokens of a request without calling the API.
+///
+/// Counts about four characters of the serialized request per token and a fixed
+/// cost per image, so base64 image data doesn't dominate the estimate.
+pub fn estimate_tokens(params: &MessageCreateParams) -> u32 {
+    let mut value = serde_json::to_value(params).unwrap_or_default();
+    let images = strip_images(&mut value);
+    let characters = value.to_string().len() as u32;
+    characters / 4 + images * IMAGE_TOKEN_ESTIMATE
+}
+
+/// Replace image blocks with null and return how many there were.
+fn strip_images(value: &mut Value) -> u32 {
+    match value {
+        Value::Object(map) if map.get("type").and_then(Value::as_str) == Some("image") => {
+            *value = Value::Null;
+            1
+        }
+        Value::Object(map) => map.values_mut().map(strip_images).sum(),
+        Value::Array(items) => items.iter_mut().map(strip_images).sum(),
+        _ => 0,
+    }
+}
+
+/// A way of shrinking the message history, used by a [`HistoryPolicy`].
+///
+/// Strategies must keep the history valid: it starts with a user message and
+/// every `tool_result` follows its `tool_use`.
+#[async_trait]
+pub trait HistoryStrategy: Send + Sync {
+    /// Shrink `params.messages` by one step.
+    ///
+    /// Returns `false` when there is nothing left for this strategy to remove.
+    async fn shrink(&self, client: &Anthropic, params: &mut MessageCreateParams) -> Result<bool>;
+}
+
+/// Keeps requests under a token budget by applying strategies to the history.
+///
+/// Before each request the policy measures the request; while it is over
+/// budget, strategies are applied in the order they were added, each until it
+/// has nothing left to remove. If the request is still too large afterwards it
+/// is sent as-is.
+///
+/// # Examples
+/// ```rust
+/// use anthropic_sdk::{Conversation, DropOldestTurns, HistoryPolicy, PruneToolResults, SummarizeHistory};
+///
+/// let policy = HistoryPolicy::new(150_000)
+///     .strategy(PruneToolResults::new().keep_recent(6))
+///     .strategy(SummarizeHistory::new().keep_recent(4))
+///     .strategy(DropOldestTurns::new());
+///
+/// let conversation = Conversation::new("claude-3-5-sonnet-latest", 1024).history_policy(policy);
+/// ```
+pub struct HistoryPolicy {
+    budget: u32,
+    measure: TokenMeasure,
+    strategies: Vec<Box<dyn HistoryStrategy>>,
+}
+
+impl HistoryPolicy {
+    /// Create a policy that keeps requests at or below `budget` input tokens.
+    pub fn new(budget: u32) -> Self {
+        Self { budget, measure: TokenMeasure::default(), strategies: Vec::new() }
+    }
+
+    /// Set how requests are measured.
+    pub fn measure(mut self, measure: TokenMeasure) -> Self {
+        self.measure = measure;
+        self
+    }
+
+    /// Add a strategy, applied after the ones already added.
+    pub fn strategy(mut self, strategy: impl HistoryStrategy + 'static) -> Self {
+        self.strategies.push(Box::new(strategy));
+        self
+    }
+
+    /// The token budget.
+    pub fn budget(&self) -> u32 {
+        self.budget
+    }
+
+    /// Measure the input tokens of a request.
+    pub async fn count(&self, client: &Anthropic, params: &MessageCreateParams) -> Result<u32> {
+        match self.measure {
+            TokenMeasure::Estimate => Ok(estimate_tokens(params)),
+            TokenMeasure::CountTokens => Ok(client.messages().count_tokens(params).await?.input_tokens),
+        }
+    }
+
+    /// Shrink the history of `params` until it fits the budget or the strategies run out.
+    ///
+    /// Returns whether the history was changed.
+    pub async fn apply(&self, client: &Anthropic, params: &mut MessageCreateParams) -> Result<bool> {
+        let mut changed = false;
+        let mut tokens = self.count(client, params).await?;
+        for strategy in &self.strategies {
+            while tokens > self.budget {
+                if !strategy.shrink(client, params).await? {
+                    break;
+                }
+                changed = true;
+                tokens = self.count(client, params).await?;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+impl fmt::Debug for HistoryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HistoryPolicy")
+            .field("budget", &self.budget)
+            .field("measure", &self.measure)
+            .field("strategies", &self.strategies.len())
+            .finish()
+    }
+}
+
+/// Indices of messages that start a turn: user messages that aren't tool results.
+///
+/// Cutting the history at one of these never separates a `tool_use` from its result.
+fn turn_starts(messages: &[MessageParam]) -> Vec<usize> {
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, message)| message.role == Role::User && !has_tool_result(&message.content))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn has_tool_result(content: &MessageContent) -> bool {
+    match content {
+        MessageContent::Text(_) => false,
+        MessageContent::Blocks(blocks) => blocks.iter().any(|block| matches!(block, ContentBlockParam::ToolResult { .. })),
+    }
+}
+
+/// Drops the oldest turns, a whole turn at a time.
+///
+/// A turn is a user message plus everything up to the next user message that
+/// isn't a tool result, so tool calls always leave together with their results.
+#[derive(Debug, Clone)]
+pub struct DropOldestTurns {
+    keep_recent: usize,
+}
+
+impl DropOldestTurns {
+    /// Drop turns until only the current one is left.
+    pub fn new() -> Self {
+        Self { keep_recent: 1 }
+    }
+
+    /// Never drop the last `turns` turns (at least one).
+    pub fn keep_recent(mut self, turns: usize) -> Self {
+        self.keep_recent = turns.max(1);
+        self
+    }
+}
+
+impl Default for DropOldestTurns {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HistoryStrategy for DropOldestTurns {
+    async fn shrink(&self, _client: &Anthropic, params: &mut MessageCreateParams) -> Result<bool> {
+        let starts = turn_starts(&params.messages);
+        let Some(&cut) = starts.iter().find(|&&index| index > 0) else {
+            return Ok(false);
+        };
+        let remaining = starts.iter().filter(|&&index| index >= cut).count();
+        if remaining < self.keep_recent {
+            return Ok(false);
+        }
+        params.messages.drain(..cut);
+        Ok(true)
+    }
+}
+
+/// Replaces the content of old tool results and images with a short placeholder.
+///
+/// The `tool_result` blocks themselves stay, so pairing with `tool_use` is preserved.
+#[derive(Debug, Clone)]
+pub struct PruneToolResults {
+    keep_recent: usize,
+    placeholder: String,
+}
+
+impl PruneToolResults {
+    /// Prune everything except the last two messages.
+    pub fn new() -> Self {
+        Self { keep_recent: 2, placeholder: "[content removed to save context]".to_string() }
+    }
+
+    /// Leave the last `messages` messages untouched.
+    pub fn keep_recent(mut self, messages: usize) -> Self {
+        self.keep_recent = messages;
+        self
+    }
+
+    /// Set the text that replaces pruned content.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    fn prune(&self, block: &mut ContentBlockParam) -> bool {
+        match block {
+            ContentBlockParam::ToolResult { content, .. } => {
+                let pruned = Some(MessageContent::Text(self.placeholder.clone()));
+                if *content == pruned {
+                    return false;
+                }
+                *content = pruned;
+                true
+            }
+            ContentBlockParam::Image { .. } => {
+                *block = ContentBlockParam::text(self.placeholder.clone());
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for PruneToolResults {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HistoryStrategy for PruneToolResults {
+    async fn shrink(&self, _client: &Anthropic, params: &mut MessageCreateParams) -> Result<bool> {
+        let end = params.messages.len().saturating_sub(self.keep_recent);
+        let mut changed = false;
+        for message in &mut params.messages[..end] {
+            if let MessageContent::Blocks(blocks) = &mut message.content {
+                for block in blocks.iter_mut() {
+                    changed |= self.prune(block);
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Replaces older turns with a summary written by the model.
+///
+/// The summary is prepended to the first kept user message, so the history
+/// still starts with a user turn.
+#[derive(Debug, Clone)]
+pub struct SummarizeHistory {
+    model: Option<String>,
+    max_tokens: u32,
+    keep_recent: usize,
+    instructions: String,
+}
+
+impl SummarizeHistory {
+    /// Summarize everything but the last two turns with the conversation's model.
+    pub fn new() -> Self {
+        Self {
+            model: None,
+            max_tokens: 1024,
+            keep_recent: 2,
+            instructions: "Summarize the conversation transcript you are given. Keep facts, decisions, \
+                names, numbers and open questions that later turns may depend on. Reply with the summary only."
+                .to_string(),
+        }
+    }
+
+    /// Use a different (e.g. cheaper) model for summaries.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Limit the length of the summary.
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Keep the last `turns` turns verbatim (at least one).
+    pub fn keep_recent(mut self, turns: usize) -> Self {
+        self.keep_recent = turns.max(1);
+        self
+    }
+
+    /// Replace the system prompt used for the summary request.
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = instructions.into();
+        self
+    }
+}
+
+impl Default for SummarizeHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl HistoryStrategy for SummarizeHistory {
+    async fn shrink(&self, client: &Anthropic, params: &mut MessageCreateParams) -> Result<bool> {
+        let starts = turn_starts(&params.messages);
+        if starts.len() <= self.keep_recent {
+            return Ok(false);
+        }
+        let cut = starts[starts.len() - self.keep_recent];
+        if cut == 0 {
+            return Ok(false);
+        }
+
+        let mut request = crate::types::MessageCreateBuilder::new(
+            self.model.clone().unwrap_or_else(|| params.model.clone()),
+            self.max_tokens,
+        )
+        .system(SystemPrompt::from(self.instructions.as_str()))
+        .user(transcript(&params.messages[..cut]))
+        .build();
+        request.metadata = params.metadata.clone();
+        let summary = client.messages().create(request).await?.text();
+
+        params.messages.drain(..cut);
+        let summary = ContentBlockParam::text(format!("Summary of the earlier conversation:\n{}", summary));
+        let first = &mut params.messages[0].content;
+        *first = match std::mem::replace(first, MessageContent::Blocks(Vec::new())) {
+            MessageContent::Text(text) => MessageContent::Blocks(vec![summary, ContentBlockParam::text(text)]),
+            MessageContent::Blocks(mut blocks) => {
+                blocks.insert(0, summary);
+                MessageContent::Blocks(blocks)
+            }
+        };
+        Ok(true)
+    }
+}
+
+/// Render messages as a plain-text transcript for summarization.
+fn transcript(messages: &[MessageParam]) -> String {
+    messages
+        .iter()
+        .map(|message| {
+            let speaker = match message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+            };
+            format!("{}: {}", speaker, content_text(&message.content))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn content_text(content: &MessageContent) -> String {
+    let blocks = match content {
+        MessageContent::Text(text) => return text.clone(),
+        MessageContent::Blocks(blocks) => blocks,
+    };
+    blocks
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlockParam::Text { text, .. } => Some(text.clone()),
+            ContentBlockParam::Image { .. } => Some("[image]".to_string()),
+            ContentBlockParam::Document { title, .. } => {
+                Some(format!("[document{}]", title.as_ref().map(|title| format!(": {}", title)).unwrap_or_default()))
+            }
+            ContentBlockParam::ToolUse { name, input, .. } | ContentBlockParam::ServerToolUse { name, input, .. } => {
+                Some(format!("[called {} with {}]", name, input))
+            }
+            ContentBlockParam::ToolResult { content, .. } => {
+                Some(format!("[tool result: {}]", content.as_ref().map(content_text).unwrap_or_default()))
+            }
+            ContentBlockParam::WebSearchToolResult { .. } => Some("[web search results]".to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockResponse, MockServer};
+    use crate::types::MessageCreateBuilder;
+    use serde_json::json;
+
+    fn tool_turn(id: &str) -> Vec<MessageParam> {
+        vec![
+            MessageParam { role: Role::User, content: format!("Look up {}", id).into() },
+            MessageParam {
+                role: Role::Assistant,
+                content: MessageContent::Blocks(vec![ContentBlockParam::ToolUse {
+                    id: id.to_string(),
+                    name: "lookup".to_string(),
+                    input: json!({"id": id}),
+                    cache_control: None,
+                }]),
+            },
+            MessageParam {
+                role: Role::User,
+                content: MessageContent::Blocks(vec![ContentBlockParam::tool_result(id, "x".repeat(4000))]),
+            },
+            MessageParam { role: Role::Assistant, content: format!("Found {}", id).into() },
+        ]
+    }
+
+    fn long_params() -> MessageCreateParams {
+        let mut params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).build();
+        for id in ["a", "b", "c"] {
+            params.messages.extend(tool_turn(id));
+        }
+        params.messages.push(MessageParam { role: Role::User, content: "Thanks".into() });
+        params
+    }
+
+    #[test]
+    fn test_estimate_tokens_charges_images_once() {
+        let mut params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").build();
+        let text_only = estimate_tokens(&params);
+        params.messages[0].content =
+            MessageContent::Blocks(vec![ContentBlockParam::image_base64("image/png", "A".repeat(100_000))]);
+        let with_image = estimate_tokens(&params);
+        assert!((IMAGE_TOKEN_ESTIMATE..IMAGE_TOKEN_ESTIMATE + 100).contains(&with_image), "{}", with_image);
+        assert!(text_only < 100);
+    }
+
+    #[test]
+    fn test_turn_starts_skip_tool_results() {
+        assert_eq!(turn_starts(&long_params().messages), vec![0, 4, 8, 12]);
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_turns_keeps_pairs() {
+        let client = Anthropic::new("test-key").unwrap();
+        let mut params = long_params();
+        let strategy = DropOldestTurns::new().keep_recent(2);
+
+        assert!(strategy.shrink(&client, &mut params).await.unwrap());
+        assert_eq!(params.messages.len(), 9);
+        assert_eq!(params.messages[0].content, MessageContent::Text("Look up b".to_string()));
+
+        assert!(strategy.shrink(&client, &mut params).await.unwrap());
+        assert_eq!(params.messages.len(), 5);
+        assert!(!strategy.shrink(&client, &mut params).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_prune_tool_results() {
+        let client = Anthropic::new("test-key").unwrap();
+        let mut params = long_params();
+        let strategy = PruneToolResults::new().keep_recent(3).placeholder("[pruned]");
+
+        assert!(strategy.shrink(&client, &mut params).await.unwrap());
+        let pruned = |index: usize| match &params.messages[index].content {
+            MessageContent::Blocks(blocks) => matches!(
+                &blocks[0],
+                ContentBlockParam::ToolResult { content: Some(MessageContent::Text(text)), .. } if text == "[pruned]"
+            ),
+            _ => false,
+        };
+        assert!(pruned(2) && pruned(6));
+        assert!(!pruned(10));
+        assert!(!strategy.shrink(&client, &mut params).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_policy_summarizes_then_drops() {
+        let server = MockServer::start().await.unwrap();
+        server.enqueue_message(MockResponse::text("They looked up a and b."));
+        let client = server.client().unwrap();
+
+        let mut params = long_params();
+        let policy = HistoryPolicy::new(estimate_tokens(&params) / 2)
+            .strategy(SummarizeHistory::new().model("claude-3-5-haiku-latest").keep_recent(2))
+            .strategy(DropOldestTurns::new());
+        assert!(policy.apply(&client, &mut params).await.unwrap());
+
+        let request = server.requests_to("POST", "/v1/messages").pop().unwrap().json();
+        assert_eq!(request["model"], "claude-3-5-haiku-latest");
+        assert!(request["messages"][0]["content"].as_str().unwrap().contains("[called lookup with {\"id\":\"a\"}]"));
+
+        // The summary fit, so no turns were dropped after it
+        assert_eq!(params.messages.len(), 5);
+        let MessageContent::Blocks(blocks) = &params.messages[0].content else {
+            panic!("Expected blocks");
+        };
+        assert!(matches!(&blocks[0], ContentBlockParam::Text { text, .. } if text.ends_with("They looked up a and b.")));
+        assert!(matches!(&blocks[1], ContentBlockParam::Text { text, .. } if text == "Look up c"));
+        assert!(estimate_tokens(&params) <= policy.budget());
+    }
+}
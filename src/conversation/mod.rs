@@ -0,0 +1,311 @@
+//! Stateful multi-turn conversations.
+
+pub mod history;
+
+pub use history::{
+    estimate_tokens, DropOldestTurns, HistoryPolicy, HistoryStrategy, PruneToolResults, SummarizeHistory,
+    TokenMeasure,
+};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::client::Anthropic;
+use crate::streaming::MessageStream;
+use crate::types::{
+    Message, MessageContent, MessageCreateParams, MessageParam, MessageStreamEvent, Result, Role, SystemPrompt,
+    ThinkingConfig, ToolChoice, ToolUnion,
+};
+
+/// A conversation that owns its request defaults and message history.
+///
+/// [`send`](Self::send) and [`stream`](Self::stream) append the user turn and
+/// Claude's reply to the history, so each call continues where the last left off.
+/// A conversation holds no client and serializes to the JSON body of the next
+/// request, which makes it easy to persist between sessions.
+///
+/// Long conversations can be kept within the context window with a
+/// [`HistoryPolicy`], applied to the history before each request.
+///
+/// # Examples
+/// ```rust,no_run
+/// use anthropic_sdk::{Anthropic, Conversation};
+///
+/// # async fn example() -> anthropic_sdk::Result<()> {
+/// let client = Anthropic::from_env()?;
+/// let mut conversation = Conversation::new("claude-3-5-sonnet-latest", 1024)
+///     .system("You are a concise assistant.");
+///
+/// let reply = conversation.send(&client, "What is the capital of France?").await?;
+/// println!("{}", reply.text());
+/// let reply = conversation.send(&client, "And its population?").await?;
+/// println!("{}", reply.text());
+///
+/// // Persist and restore later
+/// let saved = conversation.to_json()?;
+/// let restored = Conversation::from_json(&saved)?;
+/// assert_eq!(restored.messages().len(), 4);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Conversation {
+    params: MessageCreateParams,
+    #[serde(skip)]
+    history_policy: Option<Arc<HistoryPolicy>>,
+}
+
+impl Conversation {
+    /// Start an empty conversation.
+    pub fn new(model: impl Into<String>, max_tokens: u32) -> Self {
+        Self::from_params(MessageCreateParams {
+            model: model.into(),
+            max_tokens,
+            messages: Vec::new(),
+            system: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: None,
+            tools: None,
+            tool_choice: None,
+            metadata: None,
+            thinking: None,
+            service_tier: None,
+            container: None,
+            mcp_servers: None,
+            context_management: None,
+        })
+    }
+
+    /// Start a conversation from request parameters, e.g. built with
+    /// [`MessageCreateBuilder`](crate::types::MessageCreateBuilder).
+    ///
+    /// The parameters become the defaults for every turn; their messages seed the history.
+    pub fn from_params(mut params: MessageCreateParams) -> Self {
+        params.stream = None;
+        Self { params, history_policy: None }
+    }
+
+    /// Set the system prompt.
+    pub fn system(mut self, system: impl Into<SystemPrompt>) -> Self {
+        self.params.system = Some(system.into());
+        self
+    }
+
+    /// Add a tool Claude may call.
+    pub fn tool(mut self, tool: impl Into<ToolUnion>) -> Self {
+        self.params.tools.get_or_insert_with(Vec::new).push(tool.into());
+        self
+    }
+
+    /// Set the tool choice strategy.
+    pub fn tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.params.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Set the temperature.
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.params.temperature = Some(temperature);
+        self
+    }
+
+    /// Enable extended thinking.
+    pub fn thinking(mut self, thinking: ThinkingConfig) -> Self {
+        self.params.thinking = Some(thinking);
+        self
+    }
+
+    /// Shrink the history before each request to stay within the policy's budget.
+    ///
+    /// The policy works on a copy of the history, which replaces the stored
+    /// history only once the request succeeds. It is not persisted by
+    /// [`to_json`](Self::to_json); set it again after restoring.
+    pub fn history_policy(mut self, policy: HistoryPolicy) -> Self {
+        self.history_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// The message history, oldest first.
+    pub fn messages(&self) -> &[MessageParam] {
+        &self.params.messages
+    }
+
+    /// The defaults and history sent with the next turn.
+    pub fn params(&self) -> &MessageCreateParams {
+        &self.params
+    }
+
+    /// Mutable access to the defaults and history, e.g. to change the model mid-conversation.
+    pub fn params_mut(&mut self) -> &mut MessageCreateParams {
+        &mut self.params
+    }
+
+    /// Append a message to the history without sending it.
+    pub fn push(&mut self, message: MessageParam) {
+        self.params.messages.push(message);
+    }
+
+    /// Remove all messages, keeping the system prompt, tools and other defaults.
+    pub fn clear(&mut self) {
+        self.params.messages.clear();
+    }
+
+    /// Send a user turn and record Claude's reply.
+    ///
+    /// If the request fails the history is left unchanged, so the call can be retried.
+    pub async fn send(&mut self, client: &Anthropic, input: impl Into<MessageContent>) -> Result<Message> {
+        self.push_user(input);
+        let result = match self.next_request(client).await {
+            Ok(params) => {
+                let history = params.messages.clone();
+                client.messages().create(params).await.map(|message| (history, message))
+            }
+            Err(error) => Err(error),
+        };
+        self.record(result)
+    }
+
+    /// Stream a user turn and record Claude's reply once the stream completes.
+    ///
+    /// `on_event` sees every [`MessageStreamEvent`] as it arrives. If the request
+    /// or the stream fails the history is left unchanged.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// # use anthropic_sdk::{Anthropic, Conversation, ContentBlockDelta, MessageStreamEvent};
+    /// # async fn example(client: Anthropic, mut conversation: Conversation) -> anthropic_sdk::Result<()> {
+    /// let reply = conversation
+    ///     .stream(&client, "Tell me a story", |event| {
+    ///         if let MessageStreamEvent::ContentBlockDelta { delta: ContentBlockDelta::TextDelta { text }, .. } = event {
+    ///             print!("{}", text);
+    ///         }
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stream<F>(
+        &mut self,
+        client: &Anthropic,
+        input: impl Into<MessageContent>,
+        on_event: F,
+    ) -> Result<Message>
+    where
+        F: FnMut(&MessageStreamEvent),
+    {
+        self.push_user(input);
+        let result = match self.next_request(client).await {
+            Ok(params) => {
+                let history = params.messages.clone();
+                match client.messages().create_stream(params).await {
+                    Ok(stream) => Self::drain(stream, on_event).await.map(|message| (history, message)),
+                    Err(error) => Err(error),
+                }
+            }
+            Err(error) => Err(error),
+        };
+        self.record(result)
+    }
+
+    /// Serialize the conversation, including its defaults, to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Restore a conversation saved with [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// The parameters for the next request, with the history policy applied to a copy.
+    async fn next_request(&self, client: &Anthropic) -> Result<MessageCreateParams> {
+        let mut params = self.params.clone();
+        if let Some(policy) = &self.history_policy {
+            policy.apply(client, &mut params).await?;
+        }
+        Ok(params)
+    }
+
+    async fn drain<F>(mut stream: MessageStream, mut on_event: F) -> Result<Message>
+    where
+        F: FnMut(&MessageStreamEvent),
+    {
+        while let Some(event) = stream.next().await {
+            on_event(&event?);
+        }
+        stream.final_message().await
+    }
+
+    fn push_user(&mut self, input: impl Into<MessageContent>) {
+        self.push(MessageParam { role: Role::User, content: input.into() });
+    }
+
+    /// Keep the history that was sent plus the reply, or drop the new user message on failure.
+    fn record(&mut self, result: Result<(Vec<MessageParam>, Message)>) -> Result<Message> {
+        match result {
+            Ok((history, message)) => {
+                self.params.messages = history;
+                self.push(message.clone().into_param());
+                Ok(message)
+            }
+            Err(error) => {
+                self.params.messages.pop();
+                Err(error)
+            }
+        }
+    }
+}
+
+impl From<MessageCreateParams> for Conversation {
+    fn from(params: MessageCreateParams) -> Self {
+        Self::from_params(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MessageCreateBuilder, Tool};
+
+    #[test]
+    fn test_conversation_json_round_trip() {
+        let mut conversation = Conversation::new("claude-3-5-sonnet-latest", 512)
+            .system("Be brief.")
+            .tool(Tool::new("lookup", "Look something up").parameter("q", "string", "Query").build())
+            .temperature(0.3);
+        conversation.push(MessageParam { role: Role::User, content: "Hi".into() });
+        conversation.push(MessageParam { role: Role::Assistant, content: "Hello!".into() });
+
+        let json = conversation.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["model"], "claude-3-5-sonnet-latest");
+        assert_eq!(value["system"], "Be brief.");
+        assert_eq!(value["messages"][1]["role"], "assistant");
+
+        let restored = Conversation::from_json(&json).unwrap();
+        assert_eq!(restored.messages().len(), 2);
+        assert_eq!(restored.messages()[0].content, MessageContent::Text("Hi".to_string()));
+        assert_eq!(restored.params().temperature, Some(0.3));
+        assert_eq!(restored.params().tools.as_ref().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn test_conversation_from_params() {
+        let params = MessageCreateBuilder::new("claude-3-5-haiku-latest", 100)
+            .user("Earlier question")
+            .stream(true)
+            .build();
+        let mut conversation = Conversation::from(params);
+        assert_eq!(conversation.messages().len(), 1);
+        assert_eq!(conversation.params().stream, None);
+
+        conversation.clear();
+        assert!(conversation.messages().is_empty());
+        assert_eq!(conversation.params().model, "claude-3-5-haiku-latest");
+    }
+}
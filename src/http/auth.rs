@@ -12,6 +12,17 @@ pub enum AuthMethod {
     Token,
 }
 
+impl AuthMethod {
+    /// The header that carries the API key
+    pub fn header_name(&self) -> &'static str {
+        match self {
+            AuthMethod::Anthropic => "x-api-key",
+            AuthMethod::Bearer => "authorization",
+            AuthMethod::Token => "token",
+        }
+    }
+}
+
 /// Authentication handler for Anthropic API and compatible gateways
 #[derive(Debug, Clone)]
 pub struct AuthHandler {
@@ -52,6 +63,11 @@ impl AuthHandler {
         }
     }
     
+    /// The header that carries the API key
+    pub fn header_name(&self) -> &'static str {
+        self.auth_method.header_name()
+    }
+    
     /// Add authentication headers to the request
     pub fn add_auth_headers(&self, headers: &mut HeaderMap) -> Result<()> {
         match self.auth_method {
@@ -61,7 +77,7 @@ impl AuthHandler {
                         message: "Invalid API key format".to_string(),
                     })?;
                     
-                headers.insert("x-api-key", api_key_header);
+                headers.insert(self.auth_method.header_name(), api_key_header);
                 headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
             }
             AuthMethod::Bearer => {
@@ -71,7 +87,7 @@ impl AuthHandler {
                         message: "Invalid API key format for Bearer token".to_string(),
                     })?;
                     
-                headers.insert("authorization", auth_header);
+                headers.insert(self.auth_method.header_name(), auth_header);
                 headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
             }
             AuthMethod::Token => {
@@ -80,12 +96,15 @@ impl AuthHandler {
                         message: "Invalid API key format for token header".to_string(),
                     })?;
                     
-                headers.insert("token", token_header);
+                headers.insert(self.auth_method.header_name(), token_header);
                 headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
             }
         }
         
-        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        // Multipart uploads set their own content type with the form boundary
+        if !headers.contains_key("content-type") {
+            headers.insert("content-type", HeaderValue::from_static("application/json"));
+        }
         
         Ok(())
     }
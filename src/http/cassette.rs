@@ -0,0 +1,442 @@
+//! Record/replay cassettes for API interactions.
+//!
+//! A [`Cassette`] sits inside [`HttpClient`](crate::http::HttpClient) in front
+//! of its transport. In record mode every request is sent to the real API and
+//! the request/response pair, including complete SSE streams, is written to a
+//! JSON file with authentication headers scrubbed. In replay mode the file is loaded and
+//! responses are served back without touching the network, matching requests
+//! on method, path and normalized JSON body.
+//!
+//! ```rust,no_run
+//! # fn example() -> anthropic_sdk::Result<()> {
+//! use anthropic_sdk::{Anthropic, ClientConfig};
+//! use anthropic_sdk::http::Cassette;
+//!
+//! // Replays the file when it exists, records it otherwise
+//! let cassette = Cassette::auto("tests/cassettes/tool_loop.json")?;
+//! let client = Anthropic::with_config(ClientConfig::new("sk-ant-...").with_cassette(cassette))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::http::transport::HttpTransport;
+use crate::types::errors::{AnthropicError, Result};
+
+/// Headers that are never written to a cassette, besides the one carrying the API key.
+const SCRUBBED_HEADERS: &[&str] = &["x-api-key", "authorization", "proxy-authorization", "cookie", "set-cookie"];
+
+/// Placeholder stored instead of scrubbed header values.
+const REDACTED: &str = "[REDACTED]";
+
+/// Whether a cassette records new interactions or replays existing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests to the API and save the interactions
+    Record,
+    /// Serve saved interactions without network access
+    Replay,
+}
+
+/// A recorded request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// HTTP method
+    pub method: String,
+
+    /// Path relative to the configured base URL
+    pub path: String,
+
+    /// Query string, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    /// Request headers with authentication scrubbed
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// JSON body, if the request had one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// A recorded response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// HTTP status code
+    pub status: u16,
+
+    /// Response headers
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Body as text (JSON, SSE or plain text)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
+    /// Body as base64 when it is not valid UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_base64: Option<String>,
+}
+
+/// A request/response pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// On-disk cassette format.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+}
+
+/// A file of recorded API interactions.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<CassetteState>,
+}
+
+impl Cassette {
+    /// Start a new recording at `path`, replacing any existing file.
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            mode: CassetteMode::Record,
+            state: Mutex::new(CassetteState::default()),
+        }
+    }
+
+    /// Load a cassette from `path` for replay.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let content = std::fs::read_to_string(&path).map_err(|e| AnthropicError::Configuration {
+            message: format!("Failed to read cassette {}: {}", path.display(), e),
+        })?;
+        let file: CassetteFile = serde_json::from_str(&content)?;
+        let used = vec![false; file.interactions.len()];
+
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            state: Mutex::new(CassetteState { interactions: file.interactions, used }),
+        })
+    }
+
+    /// Replay `path` if it exists, otherwise record to it.
+    ///
+    /// # Errors
+    /// Returns an error if an existing file cannot be read or parsed
+    pub fn auto(path: impl AsRef<Path>) -> Result<Self> {
+        if path.as_ref().exists() {
+            Self::replay(path)
+        } else {
+            Ok(Self::record(path))
+        }
+    }
+
+    /// The cassette mode.
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// The cassette file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All interactions recorded or loaded so far.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.lock().interactions.clone()
+    }
+
+    /// Number of loaded interactions that have not been replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.lock().used.iter().filter(|used| !**used).count()
+    }
+
+    /// Write the recorded interactions to disk.
+    ///
+    /// Called automatically after each recorded interaction.
+    pub fn save(&self) -> Result<()> {
+        let file = CassetteFile { interactions: self.interactions() };
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| AnthropicError::Other(format!(
+                "Failed to create cassette directory {}: {}", parent.display(), e
+            )))?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&file)?).map_err(|e| {
+            AnthropicError::Other(format!("Failed to write cassette {}: {}", self.path.display(), e))
+        })
+    }
+
+    /// Send a request through the cassette.
+    ///
+    /// In record mode the response body is read in full before being handed
+    /// back, so streamed responses arrive all at once. `auth_header` is the
+    /// header the client's [`AuthMethod`](crate::http::auth::AuthMethod) puts the API key in.
+    pub(crate) async fn send(
+        &self,
+        transport: &dyn HttpTransport,
+        request: Request,
+        base_url: &str,
+        auth_header: &str,
+    ) -> Result<Response> {
+        let recorded = record_request(&request, base_url, auth_header);
+
+        match self.mode {
+            CassetteMode::Replay => {
+                let response = self.find(&recorded)?;
+                build_response(&response)
+            }
+            CassetteMode::Record => {
+                let response = transport.send(request).await?;
+
+                let status = response.status().as_u16();
+                let headers = record_headers(response.headers(), None);
+                let bytes = response.bytes().await?;
+                let (body, body_base64) = match std::str::from_utf8(&bytes) {
+                    Ok(text) => (Some(text.to_string()), None),
+                    Err(_) => {
+                        use base64::Engine;
+                        (None, Some(base64::engine::general_purpose::STANDARD.encode(&bytes)))
+                    }
+                };
+                let response = RecordedResponse { status, headers, body, body_base64 };
+
+                {
+                    let mut state = self.lock();
+                    state.interactions.push(Interaction { request: recorded, response: response.clone() });
+                    state.used.push(true);
+                }
+                self.save()?;
+
+                build_response(&response)
+            }
+        }
+    }
+
+    /// Take the first unused interaction matching the request.
+    fn find(&self, request: &RecordedRequest) -> Result<RecordedResponse> {
+        let mut state = self.lock();
+        let CassetteState { interactions, used } = &mut *state;
+
+        let index = interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| !*used && matches(&interaction.request, request))
+            .ok_or_else(|| AnthropicError::Other(format!(
+                "No cassette interaction in {} matches {} {}",
+                self.path.display(), request.method, request.path
+            )))?;
+
+        used[index] = true;
+        Ok(interactions[index].response.clone())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CassetteState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Requests match on method, path and normalized body.
+///
+/// Bodies that are not JSON (e.g. multipart uploads with random boundaries)
+/// are not compared.
+fn matches(recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+    recorded.method.eq_ignore_ascii_case(&request.method)
+        && recorded.path == request.path
+        && (recorded.body.is_none() || request.body.is_none() || recorded.body == request.body)
+}
+
+fn record_request(request: &Request, base_url: &str, auth_header: &str) -> RecordedRequest {
+    let url = request.url();
+    let base_path = reqwest::Url::parse(base_url)
+        .map(|base| base.path().trim_end_matches('/').to_string())
+        .unwrap_or_default();
+    let path = url.path().strip_prefix(base_path.as_str()).unwrap_or(url.path());
+
+    RecordedRequest {
+        method: request.method().to_string(),
+        path: if path.starts_with('/') { path.to_string() } else { format!("/{}", path) },
+        query: url.query().map(|q| q.to_string()),
+        headers: record_headers(request.headers(), Some(auth_header)),
+        body: request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .and_then(|bytes| serde_json::from_slice(bytes).ok()),
+    }
+}
+
+fn record_headers(headers: &reqwest::header::HeaderMap, auth_header: Option<&str>) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let scrubbed = SCRUBBED_HEADERS.contains(&name.as_str())
+                || auth_header.is_some_and(|auth_header| name.as_str().eq_ignore_ascii_case(auth_header));
+            let value = if scrubbed {
+                REDACTED.to_string()
+            } else {
+                value.to_str().unwrap_or_default().to_string()
+            };
+            (name.as_str().to_string(), value)
+        })
+        .collect()
+}
+
+fn build_response(recorded: &RecordedResponse) -> Result<Response> {
+    let body = match (&recorded.body, &recorded.body_base64) {
+        (Some(text), _) => text.clone().into_bytes(),
+        (None, Some(encoded)) => {
+            use base64::Engine;
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| AnthropicError::Other(format!("Invalid cassette body: {}", e)))?
+        }
+        (None, None) => Vec::new(),
+    };
+
+    let mut builder = http::Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        // The body is stored decoded, so framing headers no longer apply
+        if matches!(name.as_str(), "content-length" | "transfer-encoding" | "content-encoding") {
+            continue;
+        }
+        builder = builder.header(name, value);
+    }
+
+    let response = builder
+        .body(body)
+        .map_err(|e| AnthropicError::Other(format!("Invalid cassette response: {}", e)))?;
+    Ok(Response::from(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::auth::AuthMethod;
+    use crate::testing::{MockResponse, MockServer};
+    use crate::types::{ContentBlock, MessageCreateBuilder};
+    use crate::{Anthropic, ClientConfig};
+
+    fn cassette_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("anthropic-sdk-cassette-{}-{}.json", name, std::process::id()))
+    }
+
+    fn params(text: &str) -> crate::types::MessageCreateParams {
+        MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user(text).build()
+    }
+
+    #[test]
+    fn test_body_matching_ignores_key_order() {
+        let a = RecordedRequest {
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            query: None,
+            headers: BTreeMap::new(),
+            body: Some(serde_json::from_str(r#"{"model":"m","max_tokens":1}"#).unwrap()),
+        };
+        let mut b = a.clone();
+        b.body = Some(serde_json::from_str(r#"{"max_tokens":1,"model":"m"}"#).unwrap());
+        assert!(matches(&a, &b));
+
+        b.body = Some(serde_json::from_str(r#"{"max_tokens":2,"model":"m"}"#).unwrap());
+        assert!(!matches(&a, &b));
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let path = cassette_path("roundtrip");
+        let server = MockServer::start().await.unwrap();
+        server
+            .enqueue_message(MockResponse::text("Recorded reply"))
+            .enqueue_message(MockResponse::text_stream("Recorded stream"));
+
+        // Record against the mock server
+        let config = server.config().with_cassette(Cassette::record(&path));
+        let client = Anthropic::with_config(config).unwrap();
+        client.messages().create(params("Hello")).await.unwrap();
+        client.messages().create_stream(params("Stream")).await.unwrap().final_message().await.unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains(REDACTED));
+        assert!(!saved.contains(crate::testing::MOCK_API_KEY));
+        assert!(saved.contains("event: message_start"));
+
+        // Replay against an address nothing listens on
+        let cassette = Cassette::replay(&path).unwrap();
+        let config = ClientConfig::new("replay-key")
+            .with_base_url("http://127.0.0.1:9")
+            .with_cassette(cassette);
+        let client = Anthropic::with_config(config).unwrap();
+
+        let message = client.messages().create(params("Hello")).await.unwrap();
+        assert_eq!(message.content, vec![ContentBlock::Text { text: "Recorded reply".to_string(), citations: None }]);
+
+        let streamed = client.messages().create_stream(params("Stream")).await.unwrap().final_message().await.unwrap();
+        assert_eq!(streamed.content, vec![ContentBlock::Text { text: "Recorded stream".to_string(), citations: None }]);
+
+        // Each interaction is served once
+        assert!(client.messages().create(params("Hello")).await.is_err());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_record_scrubs_token_auth_header() {
+        let path = cassette_path("token-auth");
+        let server = MockServer::start().await.unwrap();
+        server.enqueue_message(MockResponse::text("Recorded reply"));
+
+        let config = server
+            .config()
+            .with_auth_method(AuthMethod::Token)
+            .with_cassette(Cassette::record(&path));
+        let client = Anthropic::with_config(config).unwrap();
+        client.messages().create(params("Hello")).await.unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains(r#""token": "[REDACTED]""#));
+        assert!(!saved.contains(crate::testing::MOCK_API_KEY));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_replay_records_errors() {
+        let path = cassette_path("errors");
+        let server = MockServer::start().await.unwrap();
+        server.enqueue_message(MockResponse::rate_limited());
+
+        let client = Anthropic::with_config(server.config().with_cassette(Cassette::record(&path))).unwrap();
+        assert!(client.messages().create(params("Hi")).await.is_err());
+
+        let client = Anthropic::with_config(
+            ClientConfig::new("replay-key").with_cassette(Cassette::replay(&path).unwrap()),
+        )
+        .unwrap();
+        let error = client.messages().create(params("Hi")).await.unwrap_err();
+        assert!(matches!(error, AnthropicError::RateLimit { .. }));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
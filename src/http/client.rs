@@ -1,13 +1,17 @@
+use std::sync::Arc;
 use reqwest::{Client, Request, Response, RequestBuilder};
 use serde_json::Value;
 use crate::config::ClientConfig;
 use crate::http::auth::AuthHandler;
+use crate::http::transport::{HttpTransport, ReqwestTransport};
 use crate::types::errors::{AnthropicError, Result};
 use crate::types::shared::RequestId;
 
 #[derive(Debug, Clone)]
 pub struct HttpClient {
     client: Client,
+    transport: Arc<dyn HttpTransport>,
+    base_url: String,
     config: ClientConfig,
     auth: AuthHandler,
 }
@@ -17,14 +21,42 @@ impl HttpClient {
         // Validate configuration before creating client
         config.validate()?;
         
-        let client = Client::builder()
-            .timeout(config.timeout)
-            .build()
-            .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
+        let default_transport = ReqwestTransport::new(config.timeout)?;
+        let client = default_transport.client().clone();
+        let (transport, base_url) = Self::select_transport(&config, default_transport);
             
         let auth = AuthHandler::with_method(config.api_key.clone(), config.auth_method.clone());
             
-        Ok(Self { client, config, auth })
+        Ok(Self { client, transport, base_url, config, auth })
+    }
+    
+    /// Pick the transport and the base URL requests are built against
+    fn select_transport(config: &ClientConfig, default: ReqwestTransport) -> (Arc<dyn HttpTransport>, String) {
+        #[cfg(unix)]
+        {
+            use crate::http::unix::{socket_path, UnixSocketTransport};
+            
+            // `unix:///path.sock` addresses the socket itself; requests go to its root
+            let (socket, base_url) = match socket_path(&config.base_url) {
+                Some(path) => (Some(std::path::PathBuf::from(path)), "http://localhost".to_string()),
+                None => (config.unix_socket.clone(), config.base_url.clone()),
+            };
+            
+            if let Some(transport) = &config.transport {
+                return (transport.clone(), base_url);
+            }
+            if let Some(socket) = socket {
+                let transport = UnixSocketTransport::new(socket).with_timeout(config.timeout);
+                return (Arc::new(transport), base_url);
+            }
+            (Arc::new(default), base_url)
+        }
+        
+        #[cfg(not(unix))]
+        {
+            let transport = config.transport.clone().unwrap_or_else(|| Arc::new(default));
+            (transport, config.base_url.clone())
+        }
     }
     
     /// Send a prepared request with authentication and error handling
@@ -33,8 +65,12 @@ impl HttpClient {
         let headers = request.headers_mut();
         self.auth.add_auth_headers(headers)?;
         
-        let response = self.client.execute(request).await
-            .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
+        let response = match &self.config.cassette {
+            Some(cassette) => {
+                cassette.send(self.transport.as_ref(), request, &self.base_url, self.auth.header_name()).await?
+            }
+            None => self.transport.send(request).await?,
+        };
             
         self.handle_response_status(response).await
     }
@@ -61,7 +97,7 @@ impl HttpClient {
     
     /// Build a full URL from a path
     pub fn build_url(&self, path: &str) -> String {
-        format!("{}{}", self.config.base_url.trim_end_matches('/'), path)
+        format!("{}{}", self.base_url.trim_end_matches('/'), path)
     }
     
     /// Handle response status codes and convert to appropriate errors
@@ -102,9 +138,11 @@ impl HttpClient {
             .map(|id| RequestId::new(id.to_string()))
     }
     
-    /// Get the base URL
+    /// Get the base URL requests are built against
+    /// 
+    /// For `unix://` base URLs this is `http://localhost`.
     pub fn base_url(&self) -> &str {
-        &self.config.base_url
+        &self.base_url
     }
     
     /// Get the current configuration
@@ -115,7 +153,14 @@ impl HttpClient {
     /// Get the underlying reqwest client
     /// 
     /// This is useful for creating custom requests or integrating with other libraries.
+    /// Requests should still be sent with [`HttpClient::send`] so that the
+    /// configured transport is used.
     pub fn client(&self) -> &Client {
         &self.client
     }
+    
+    /// Get the transport used to send requests
+    pub fn transport(&self) -> &Arc<dyn HttpTransport> {
+        &self.transport
+    }
 } 
\ No newline at end of file
@@ -2,9 +2,17 @@ pub mod client;
 pub mod auth;
 pub mod streaming;
 pub mod retry;
+pub mod cassette;
+pub mod transport;
+#[cfg(unix)]
+pub mod unix;
 
 // Re-exports for convenience
 pub use client::HttpClient;
 pub use auth::AuthHandler;
 pub use streaming::{HttpStreamClient, StreamRequestBuilder, StreamConfig};
+pub use transport::{HttpTransport, ReqwestTransport};
+#[cfg(unix)]
+pub use unix::UnixSocketTransport;
+pub use cassette::{Cassette, CassetteMode, Interaction, RecordedRequest, RecordedResponse};
 pub use retry::{RetryPolicy, RetryCondition, RetryExecutor, RetryResult, default_retry, api_retry}; 
\ No newline at end of file
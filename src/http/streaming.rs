@@ -12,6 +12,7 @@ use serde_json;
 use tokio::sync::broadcast;
 use tokio_stream::StreamExt;
 
+use crate::http::HttpClient;
 use crate::types::{MessageStreamEvent, AnthropicError, Result};
 
 /// Configuration for SSE streaming requests.
@@ -106,6 +107,8 @@ impl HttpStreamClient {
         
         let sse_stream = byte_stream
             .eventsource()
+            // Keep-alive pings carry no data for the caller
+            .filter(|result| !matches!(result, Ok(event) if event.event == "ping"))
             .map(|result| {
                 match result {
                     Ok(event) => {
@@ -214,17 +217,27 @@ impl HttpStreamClient {
                                 // Message stop doesn't need data parsing
                                 Ok(MessageStreamEvent::MessageStop)
                             }
-                            // Handle other event types
-                            "ping" => {
-                                // Ignore ping events
-                                Err(AnthropicError::StreamError("ping".to_string()))
+                            "error" => {
+                                // Errors reported mid-stream, e.g. overloaded_error
+                                let error = serde_json::from_str::<serde_json::Value>(&event.data)
+                                    .ok()
+                                    .and_then(|value| {
+                                        let error = value.get("error")?;
+                                        Some(format!(
+                                            "{}: {}",
+                                            error["type"].as_str().unwrap_or("error"),
+                                            error["message"].as_str().unwrap_or_default()
+                                        ))
+                                    })
+                                    .unwrap_or_else(|| event.data.clone());
+                                Err(AnthropicError::StreamError(error))
                             }
                             event_type => {
-                                // Log unknown event types but don't fail
+                                // Pass unknown event types through so new API events don't break the stream
                                 tracing::debug!("Unknown SSE event type: {}", event_type);
-                                Err(AnthropicError::StreamError(
-                                    format!("Unknown event type: {}", event_type)
-                                ))
+                                serde_json::from_str::<MessageStreamEvent>(&event.data).map_err(|e| {
+                                    AnthropicError::StreamError(format!("Failed to parse {} event: {}", event_type, e))
+                                })
                             }
                         }
                     }
@@ -232,13 +245,6 @@ impl HttpStreamClient {
                         format!("SSE stream error: {}", e)
                     )),
                 }
-            })
-            .filter_map(|result| {
-                match result {
-                    Ok(event) => Some(Ok(event)),
-                    Err(e) if e.to_string().contains("ping") => None, // Filter out ping errors
-                    Err(e) => Some(Err(e)),
-                }
             });
 
         Ok(sse_stream)
@@ -296,12 +302,13 @@ impl Stream for HttpStreamClient {
 }
 
 /// Builder for creating HTTP streaming requests.
+///
+/// Requests are prepared and sent by the shared [`HttpClient`], so they pick
+/// up authentication, the configured transport and any cassette.
 #[derive(Debug, Clone)]
 pub struct StreamRequestBuilder {
-    /// HTTP client for making requests
-    client: reqwest::Client,
-    /// Base URL for the API
-    base_url: String,
+    /// HTTP client for preparing and sending requests
+    http_client: HttpClient,
     /// Request headers
     headers: reqwest::header::HeaderMap,
     /// Stream configuration
@@ -310,10 +317,9 @@ pub struct StreamRequestBuilder {
 
 impl StreamRequestBuilder {
     /// Create a new stream request builder.
-    pub fn new(client: reqwest::Client, base_url: String) -> Self {
+    pub fn new(http_client: HttpClient) -> Self {
         Self {
-            client,
-            base_url,
+            http_client,
             headers: reqwest::header::HeaderMap::new(),
             config: StreamConfig::default(),
         }
@@ -342,7 +348,7 @@ impl StreamRequestBuilder {
         endpoint: &str,
         body: &T,
     ) -> Result<HttpStreamClient> {
-        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), endpoint.trim_start_matches('/'));
+        let url = self.http_client.build_url(&format!("/{}", endpoint.trim_start_matches('/')));
         
         let mut headers = self.headers;
         headers.insert(
@@ -354,14 +360,14 @@ impl StreamRequestBuilder {
             reqwest::header::HeaderValue::from_static("no-cache"),
         );
 
-        let response = self
-            .client
+        let request = self
+            .http_client
             .post(&url)
             .headers(headers)
             .json(body)
-            .send()
-            .await
+            .build()
             .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
+        let response = self.http_client.send(request).await?;
 
         HttpStreamClient::from_response(response, self.config).await
     }
@@ -382,17 +388,31 @@ mod tests {
 
     #[test]
     fn test_stream_request_builder() {
-        let client = reqwest::Client::new();
-        let builder = StreamRequestBuilder::new(client, "https://api.anthropic.com".to_string())
-            .header("Authorization", "Bearer test-key")
+        let http_client = HttpClient::new(crate::ClientConfig::new("test-key")).unwrap();
+        let builder = StreamRequestBuilder::new(http_client)
+            .header("anthropic-beta", "test-beta")
             .config(StreamConfig {
                 buffer_size: 500,
                 ..Default::default()
             });
 
-        assert_eq!(builder.base_url, "https://api.anthropic.com");
+        assert_eq!(builder.http_client.base_url(), "https://api.anthropic.com");
         assert_eq!(builder.config.buffer_size, 500);
-        assert!(builder.headers.contains_key("authorization"));
+        assert!(builder.headers.contains_key("anthropic-beta"));
+    }
+
+    #[test]
+    fn test_unknown_event_parsing() {
+        let event: MessageStreamEvent = serde_json::from_str(r#"{"type":"ping"}"#).unwrap();
+        assert_eq!(event, MessageStreamEvent::Unknown(serde_json::json!({"type": "ping"})));
+
+        let delta = r#"{"type":"content_block_delta","index":0,"delta":{"type":"sparkle_delta","sparkle":"*"}}"#;
+        match serde_json::from_str::<MessageStreamEvent>(delta).unwrap() {
+            MessageStreamEvent::ContentBlockDelta { delta: crate::types::ContentBlockDelta::Unknown(value), .. } => {
+                assert_eq!(value["sparkle"], "*");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
     }
 
     #[tokio::test]
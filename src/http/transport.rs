@@ -0,0 +1,155 @@
+//! Pluggable HTTP transport.
+//!
+//! [`HttpClient`](crate::http::HttpClient) prepares requests (URL, headers,
+//! authentication, body) and hands them to an [`HttpTransport`] to send. The
+//! default transport is [`ReqwestTransport`]; supply your own through
+//! [`ClientConfig::with_transport`](crate::ClientConfig::with_transport) to
+//! route requests through an in-memory test double, a custom connector or a
+//! local sidecar.
+//!
+//! Responses are returned as `reqwest::Response`, which can be built from any
+//! `http::Response` whose body is either buffered or a byte stream:
+//!
+//! ```rust
+//! use anthropic_sdk::http::HttpTransport;
+//! use anthropic_sdk::Result;
+//!
+//! #[derive(Debug)]
+//! struct StaticTransport;
+//!
+//! #[async_trait::async_trait]
+//! impl HttpTransport for StaticTransport {
+//!     async fn send(&self, _request: reqwest::Request) -> Result<reqwest::Response> {
+//!         let response = http::Response::builder()
+//!             .status(200)
+//!             .header("content-type", "application/json")
+//!             .body(r#"{"data":[],"has_more":false,"first_id":null,"last_id":null}"#)
+//!             .unwrap();
+//!         Ok(reqwest::Response::from(response))
+//!     }
+//! }
+//! ```
+
+use std::fmt::Debug;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::{Client, Request, Response};
+
+use crate::types::errors::{AnthropicError, Result};
+
+/// Sends prepared HTTP requests.
+///
+/// Implementations receive fully prepared requests, including authentication
+/// headers, and must return the response without consuming its body so that
+/// streaming endpoints keep working. Error statuses are not errors at this
+/// level; the client converts them.
+#[async_trait]
+pub trait HttpTransport: Send + Sync + Debug {
+    /// Send a request and return the (possibly streaming) response.
+    async fn send(&self, request: Request) -> Result<Response>;
+}
+
+/// The default transport, backed by `reqwest::Client`.
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Create a transport with the given request timeout.
+    pub fn new(timeout: Duration) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
+        Ok(Self { client })
+    }
+
+    /// Wrap an existing `reqwest::Client`.
+    pub fn from_client(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the underlying reqwest client
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: Request) -> Result<Response> {
+        self.client
+            .execute(request)
+            .await
+            .map_err(|e| AnthropicError::Connection { message: e.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures;
+    use crate::types::{ContentBlock, MessageCreateBuilder};
+    use crate::{Anthropic, ClientConfig};
+    use std::sync::{Arc, Mutex};
+
+    /// Serves canned responses and records request paths.
+    #[derive(Debug, Default)]
+    struct InMemoryTransport {
+        paths: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl HttpTransport for InMemoryTransport {
+        async fn send(&self, request: Request) -> Result<Response> {
+            self.paths.lock().unwrap().push(request.url().path().to_string());
+
+            let stream = request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(bytes).ok())
+                .map(|body| body["stream"] == true)
+                .unwrap_or(false);
+
+            let message = fixtures::text_message("From memory");
+            let response = if stream {
+                // Deliver the SSE body as a stream of frames
+                let frames: Vec<std::result::Result<bytes::Bytes, std::io::Error>> =
+                    crate::testing::message_events(&message)
+                        .into_iter()
+                        .map(|(event, data)| Ok(format!("event: {}\ndata: {}\n\n", event, data).into()))
+                        .collect();
+                http::Response::builder()
+                    .status(200)
+                    .header("content-type", "text/event-stream")
+                    .body(reqwest::Body::wrap_stream(futures::stream::iter(frames)))
+                    .unwrap()
+            } else {
+                http::Response::builder()
+                    .status(200)
+                    .header("content-type", "application/json")
+                    .body(reqwest::Body::from(serde_json::to_vec(&message).unwrap()))
+                    .unwrap()
+            };
+            Ok(Response::from(response))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport_for_json_and_streams() {
+        let transport = InMemoryTransport::default();
+        let paths = transport.paths.clone();
+        let client = Anthropic::with_config(ClientConfig::new("test-key").with_transport(transport)).unwrap();
+
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").build();
+        let message = client.messages().create(params.clone()).await.unwrap();
+        assert_eq!(message.content, vec![ContentBlock::Text { text: "From memory".to_string(), citations: None }]);
+
+        let streamed = client.messages().create_stream(params).await.unwrap().final_message().await.unwrap();
+        assert_eq!(streamed.content, vec![ContentBlock::Text { text: "From memory".to_string(), citations: None }]);
+
+        assert_eq!(*paths.lock().unwrap(), vec!["/v1/messages", "/v1/messages"]);
+    }
+}
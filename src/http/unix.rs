@@ -0,0 +1,182 @@
+//! Unix domain socket transport.
+//!
+//! Used when the base URL is `unix:///path/to.sock` or a socket path is set
+//! with [`ClientConfig::with_unix_socket`](crate::ClientConfig::with_unix_socket).
+//! Each request opens a new connection to the socket and speaks HTTP/1.1 over
+//! it, so messages, streaming, files and batches all work unchanged.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hyper_util::rt::TokioIo;
+use reqwest::{Request, Response};
+use tokio::net::UnixStream;
+
+use crate::http::transport::HttpTransport;
+use crate::types::errors::{AnthropicError, Result};
+
+/// URL scheme selecting the Unix socket transport.
+pub const UNIX_SCHEME: &str = "unix://";
+
+/// Sends requests over a Unix domain socket.
+#[derive(Debug, Clone)]
+pub struct UnixSocketTransport {
+    path: PathBuf,
+    timeout: Option<Duration>,
+}
+
+impl UnixSocketTransport {
+    /// Create a transport connecting to the socket at `path`.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self { path: path.as_ref().to_path_buf(), timeout: None }
+    }
+
+    /// Fail requests whose response headers do not arrive within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The socket path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn execute(&self, mut request: Request) -> Result<Response> {
+        let stream = UnixStream::connect(&self.path).await.map_err(|e| AnthropicError::Connection {
+            message: format!("Failed to connect to {}: {}", self.path.display(), e),
+        })?;
+
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::debug!("Unix socket connection closed with error: {}", e);
+            }
+        });
+
+        // Requests over a socket use origin-form targets with the URL host in `Host`
+        let url = request.url().clone();
+        let target = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or("localhost"), port),
+            None => url.host_str().unwrap_or("localhost").to_string(),
+        };
+
+        let mut builder = http::Request::builder()
+            .method(request.method().clone())
+            .uri(target)
+            .header(http::header::HOST, host);
+        for (name, value) in request.headers() {
+            builder = builder.header(name, value);
+        }
+        let body = request.body_mut().take().unwrap_or_else(|| reqwest::Body::from(Vec::new()));
+        let request = builder
+            .body(body)
+            .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
+
+        let response = sender
+            .send_request(request)
+            .await
+            .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
+
+        Ok(Response::from(response.map(reqwest::Body::wrap)))
+    }
+}
+
+#[async_trait]
+impl HttpTransport for UnixSocketTransport {
+    async fn send(&self, request: Request) -> Result<Response> {
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.execute(request))
+                .await
+                .map_err(|_| AnthropicError::Timeout)?,
+            None => self.execute(request).await,
+        }
+    }
+}
+
+/// Extract the socket path from a `unix://` base URL.
+pub(crate) fn socket_path(base_url: &str) -> Option<&str> {
+    base_url.strip_prefix(UNIX_SCHEME).filter(|path| !path.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockResponse, MockServer};
+    use crate::types::{ContentBlock, FilePurpose, FileUploadParams, MessageCreateBuilder};
+    use crate::{Anthropic, ClientConfig};
+
+    fn socket(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("anthropic-sdk-{}-{}.sock", name, std::process::id()))
+    }
+
+    fn params() -> crate::types::MessageCreateParams {
+        MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hello").build()
+    }
+
+    #[test]
+    fn test_socket_path() {
+        assert_eq!(socket_path("unix:///tmp/gateway.sock"), Some("/tmp/gateway.sock"));
+        assert_eq!(socket_path("unix://"), None);
+        assert_eq!(socket_path("http://localhost"), None);
+    }
+
+    #[tokio::test]
+    async fn test_messages_and_streaming_over_socket() {
+        let server = MockServer::start_unix(socket("messages")).await.unwrap();
+        server
+            .enqueue_message(MockResponse::text("Over the socket"))
+            .enqueue_message(MockResponse::text_stream("Streamed over the socket"));
+
+        let client = server.client().unwrap();
+        assert!(server.url().starts_with(UNIX_SCHEME));
+
+        let message = client.messages().create(params()).await.unwrap();
+        assert_eq!(message.content, vec![ContentBlock::Text { text: "Over the socket".to_string(), citations: None }]);
+
+        let streamed = client.messages().create_stream(params()).await.unwrap().final_message().await.unwrap();
+        assert_eq!(streamed.content, vec![ContentBlock::Text { text: "Streamed over the socket".to_string(), citations: None }]);
+
+        let request = server.last_request().unwrap();
+        assert_eq!(request.path, "/v1/messages");
+        assert_eq!(request.header("host"), Some("localhost"));
+    }
+
+    #[tokio::test]
+    async fn test_files_over_explicit_socket_path() {
+        let path = socket("files");
+        let server = MockServer::start_unix(&path).await.unwrap();
+
+        let config = ClientConfig::new("test-key")
+            .with_base_url("http://gateway.local/anthropic")
+            .with_unix_socket(&path);
+        let client = Anthropic::with_config(config).unwrap();
+
+        server.enqueue("POST", "/anthropic/v1/files", MockResponse::json(
+            crate::testing::fixtures::file_object("notes.txt", "text/plain", 5),
+        ));
+        let upload = FileUploadParams::new(b"hello".to_vec(), "notes.txt", "text/plain", FilePurpose::Document);
+        let file = client.files().upload(upload).await.unwrap();
+        assert_eq!(file.filename, "notes.txt");
+
+        let request = server.last_request().unwrap();
+        assert_eq!(request.path, "/anthropic/v1/files");
+        assert_eq!(request.header("host"), Some("gateway.local"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_socket_is_connection_error() {
+        let config = ClientConfig::new("test-key").with_base_url(format!("unix://{}", socket("missing").display()));
+        let client = Anthropic::with_config(config).unwrap();
+
+        let error = client.messages().create(params()).await.unwrap_err();
+        assert!(matches!(error, AnthropicError::Connection { .. }), "{:?}", error);
+    }
+}
@@ -28,6 +28,7 @@
 //! - **Error Handling**: Comprehensive error types
 //! - **Logging**: Configurable tracing support
 //! - **Async/Await**: Built on tokio for high performance
+//! - **Testing**: In-process mock server behind the `testing` feature
 //!
 
 pub mod client;
@@ -40,6 +41,10 @@ pub mod streaming;
 pub mod tools;
 pub mod files;
 pub mod tokens;
+pub mod conversation;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 // Re-exports for public API
 pub use client::Anthropic;
@@ -48,13 +53,18 @@ pub use types::{
     AnthropicError, Result, RequestId, Usage,
     Message, Role, ContentBlock, ImageSource, StopReason,
     MessageCreateParams, MessageParam, MessageContent, ContentBlockParam,
-    MessageCreateBuilder, Model,
+    MessageCreateBuilder, ThinkingConfig, SystemPrompt, SystemBlock, CacheControl, CacheTtl, Model,
+    RequestValidationError,
+    DocumentSource, CitationsConfig, CitedSource, MessageCountTokensParams, MessageTokensCount,
+    Metadata, ServiceTier, ServiceTierPreference, McpServer, McpToolConfiguration,
+    ContextManagement, ContextEdit, ContextThreshold, ContinuationLimits,
     // Streaming types
     MessageStreamEvent, MessageDelta, MessageDeltaUsage,
     ContentBlockDelta, TextCitation,
     // Tool types
     Tool, ToolBuilder, ToolChoice, ToolUse, ToolResult, ToolResultContent,
-    ToolValidationError, ServerTool, WebSearchParameters,
+    ToolValidationError, SchemaViolation, ServerTool, WebSearchParameters, UserLocation, ToolUnion,
+    WebSearchResult, WebSearchToolResultError, WebSearchErrorCode, WebSearchToolResultContent,
     // Batch types (Beta)
     MessageBatch, BatchStatus, BatchRequestCounts, BatchRequest, BatchRequestBuilder,
     BatchResult, BatchResponse, BatchResponseBody, BatchError,
@@ -87,8 +97,26 @@ pub use http::{
     RetryPolicy, RetryCondition, RetryExecutor, RetryResult, default_retry, api_retry,
 };
 pub use streaming::MessageStream;
+pub use conversation::{
+    Conversation, HistoryPolicy, HistoryStrategy, TokenMeasure, DropOldestTurns, PruneToolResults,
+    SummarizeHistory,
+};
 pub use http::auth::AuthMethod;
 
+/// Re-exported so derived schemas match the version the SDK was built with
+#[cfg(feature = "schemars")]
+pub use schemars;
+
+/// Paths used by code generated by the `macros` feature; not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use async_trait;
+    pub use serde;
+    pub use serde_json;
+    #[cfg(feature = "schemars")]
+    pub use crate::tools::input::ToolSchemaBuilder;
+}
+
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -30,12 +30,13 @@ impl BatchesResource {
     /// # Errors
     /// Returns an error if the request fails or if the batch parameters are invalid
     pub async fn create(&self, params: BatchCreateParams) -> Result<MessageBatch> {
-        let response = self
+        let url = self.http_client.build_url("/v1/messages/batches");
+        let request = self
             .http_client
-            .post("/v1/messages/batches")
+            .post(&url)
             .json(&params)
-            .send()
-            .await?;
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let batch: MessageBatch = response.json().await?;
         Ok(batch)
@@ -52,11 +53,12 @@ impl BatchesResource {
     /// # Errors
     /// Returns an error if the batch is not found or if the request fails
     pub async fn get(&self, batch_id: &str) -> Result<MessageBatch> {
-        let response = self
+        let url = self.http_client.build_url(&format!("/v1/messages/batches/{}", batch_id));
+        let request = self
             .http_client
-            .get(&format!("/v1/messages/batches/{}", batch_id))
-            .send()
-            .await?;
+            .get(&url)
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let batch: MessageBatch = response.json().await?;
         Ok(batch)
@@ -73,7 +75,8 @@ impl BatchesResource {
     /// # Errors
     /// Returns an error if the request fails
     pub async fn list(&self, params: Option<BatchListParams>) -> Result<BatchList> {
-        let mut request = self.http_client.get("/v1/messages/batches");
+        let url = self.http_client.build_url("/v1/messages/batches");
+        let mut request = self.http_client.get(&url);
 
         if let Some(params) = params {
             if let Some(after) = params.after {
@@ -84,7 +87,7 @@ impl BatchesResource {
             }
         }
 
-        let response = request.send().await?;
+        let response = self.http_client.send(request.build()?).await?;
         let batch_list: BatchList = response.json().await?;
         Ok(batch_list)
     }
@@ -100,11 +103,12 @@ impl BatchesResource {
     /// # Errors
     /// Returns an error if the batch cannot be cancelled or if the request fails
     pub async fn cancel(&self, batch_id: &str) -> Result<MessageBatch> {
-        let response = self
+        let url = self.http_client.build_url(&format!("/v1/messages/batches/{}/cancel", batch_id));
+        let request = self
             .http_client
-            .post(&format!("/v1/messages/batches/{}/cancel", batch_id))
-            .send()
-            .await?;
+            .post(&url)
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let batch: MessageBatch = response.json().await?;
         Ok(batch)
@@ -135,11 +139,12 @@ impl BatchesResource {
         })?;
 
         // Download the results file
-        let response = self
+        let url = self.http_client.build_url(&format!("/v1/files/{}/content", output_file_id));
+        let request = self
             .http_client
-            .get(&format!("/v1/files/{}/content", output_file_id))
-            .send()
-            .await?;
+            .get(&url)
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let content = response.text().await?;
 
@@ -213,11 +218,9 @@ impl BatchesResource {
     /// Returns an error if the request fails
     pub async fn get_status(&self, batch_id: &str) -> Result<(crate::types::BatchStatus, f64, u32)> {
         let batch = self.get(batch_id).await?;
-        Ok((
-            batch.processing_status,
-            batch.completion_percentage(),
-            batch.pending_requests(),
-        ))
+        let completion = batch.completion_percentage();
+        let pending = batch.pending_requests();
+        Ok((batch.processing_status, completion, pending))
     }
 }
 
@@ -358,7 +361,7 @@ mod tests {
         assert_eq!(request.method, "POST");
         assert_eq!(request.url, "/v1/messages");
         assert!(request.body.messages.len() > 0);
-        assert_eq!(request.body.system, Some("You are helpful".to_string()));
+        assert_eq!(request.body.system, Some("You are helpful".into()));
         assert_eq!(request.body.temperature, Some(0.7));
     }
 } 
\ No newline at end of file
@@ -36,12 +36,13 @@ impl FilesResource {
         // Create multipart form
         let form = self.create_multipart_form(params)?;
 
-        let response = self
+        let url = self.http_client.build_url("/v1/files");
+        let request = self
             .http_client
-            .post("/v1/files")
+            .post(&url)
             .multipart(form)
-            .send()
-            .await?;
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let file_object: FileObject = response.json().await?;
         Ok(file_object)
@@ -105,11 +106,12 @@ impl FilesResource {
     /// # Errors
     /// Returns an error if the file is not found or if the request fails
     pub async fn get(&self, file_id: &str) -> Result<FileObject> {
-        let response = self
+        let url = self.http_client.build_url(&format!("/v1/files/{}", file_id));
+        let request = self
             .http_client
-            .get(&format!("/v1/files/{}", file_id))
-            .send()
-            .await?;
+            .get(&url)
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let file_object: FileObject = response.json().await?;
         Ok(file_object)
@@ -126,7 +128,8 @@ impl FilesResource {
     /// # Errors
     /// Returns an error if the request fails
     pub async fn list(&self, params: Option<FileListParams>) -> Result<FileList> {
-        let mut request = self.http_client.get("/v1/files");
+        let url = self.http_client.build_url("/v1/files");
+        let mut request = self.http_client.get(&url);
 
         if let Some(params) = params {
             if let Some(purpose) = params.purpose {
@@ -143,7 +146,7 @@ impl FilesResource {
             }
         }
 
-        let response = request.send().await?;
+        let response = self.http_client.send(request.build()?).await?;
         let file_list: FileList = response.json().await?;
         Ok(file_list)
     }
@@ -159,11 +162,12 @@ impl FilesResource {
     /// # Errors
     /// Returns an error if the file is not found or cannot be downloaded
     pub async fn download(&self, file_id: &str) -> Result<FileDownload> {
-        let response = self
+        let url = self.http_client.build_url(&format!("/v1/files/{}/content", file_id));
+        let request = self
             .http_client
-            .get(&format!("/v1/files/{}/content", file_id))
-            .send()
-            .await?;
+            .get(&url)
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let content_type = response
             .headers()
@@ -202,11 +206,12 @@ impl FilesResource {
     /// # Errors
     /// Returns an error if the file cannot be deleted or if the request fails
     pub async fn delete(&self, file_id: &str) -> Result<FileObject> {
-        let response = self
+        let url = self.http_client.build_url(&format!("/v1/files/{}", file_id));
+        let request = self
             .http_client
-            .delete(&format!("/v1/files/{}", file_id))
-            .send()
-            .await?;
+            .delete(&url)
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let file_object: FileObject = response.json().await?;
         Ok(file_object)
@@ -220,11 +225,12 @@ impl FilesResource {
     /// # Errors
     /// Returns an error if the request fails
     pub async fn get_storage_info(&self) -> Result<StorageInfo> {
-        let response = self
+        let url = self.http_client.build_url("/v1/files/storage");
+        let request = self
             .http_client
-            .get("/v1/files/storage")
-            .send()
-            .await?;
+            .get(&url)
+            .build()?;
+        let response = self.http_client.send(request).await?;
 
         let storage_info: StorageInfo = response.json().await?;
         Ok(storage_info)
@@ -7,12 +7,56 @@ use crate::http::streaming::{StreamRequestBuilder, StreamConfig};
 /// Messages API resource for interacting with Claude
 pub struct MessagesResource<'a> {
     client: &'a Anthropic,
+    max_continuations: Option<u32>,
+    validate_requests: bool,
 }
 
 impl<'a> MessagesResource<'a> {
     /// Create a new Messages resource
     pub fn new(client: &'a Anthropic) -> Self {
-        Self { client }
+        Self { client, max_continuations: None, validate_requests: false }
+    }
+    
+    /// Automatically resubmit turns paused by long-running server tools
+    ///
+    /// When a response stops with [`StopReason::PauseTurn`], [`create`](Self::create)
+    /// sends the paused content back and keeps going, up to `max_continuations`
+    /// extra requests. The returned message holds the content of the whole turn
+    /// and the usage summed across requests; if the limit is reached it still
+    /// stops with `PauseTurn`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use anthropic_sdk::{Anthropic, ServerTool, types::MessageCreateBuilder};
+    ///
+    /// let client = Anthropic::from_env()?;
+    /// let message = client.messages()
+    ///     .auto_continue(5)
+    ///     .create(
+    ///         MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+    ///             .tool(ServerTool::web_search())
+    ///             .user("Research the latest Rust release")
+    ///             .build()
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn auto_continue(mut self, max_continuations: u32) -> Self {
+        self.max_continuations = Some(max_continuations);
+        self
+    }
+    
+    /// Check requests with [`MessageCreateParams::validate`] before sending them
+    ///
+    /// Invalid requests fail with [`AnthropicError::InvalidRequest`] instead of
+    /// a 400 from the API. Applies to [`create`](Self::create) and
+    /// [`create_stream`](Self::create_stream).
+    pub fn validate_requests(mut self) -> Self {
+        self.validate_requests = true;
+        self
     }
     
     /// Create a message with Claude
@@ -39,11 +83,239 @@ impl<'a> MessagesResource<'a> {
     /// # }
     /// ```
     pub async fn create(&self, params: MessageCreateParams) -> Result<Message> {
+        if self.validate_requests {
+            params.validate()?;
+        }
+        match self.max_continuations {
+            Some(max_continuations) => self.create_continuing(params, max_continuations).await,
+            None => self.send(&params).await,
+        }
+    }
+    
+    /// Extract a typed value by forcing Claude to call a tool whose schema is derived from `T`
+    ///
+    /// The tool input is deserialized into `T`. When it doesn't match, the error is
+    /// sent back as a failed tool result and Claude is asked again, up to
+    /// `max_retries` times, before failing with [`AnthropicError::StructuredOutput`].
+    /// Requires the `schemars` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use anthropic_sdk::{Anthropic, MessageCreateBuilder};
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    ///
+    /// /// Contact details found in the text.
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct Contact {
+    ///     name: String,
+    ///     email: Option<String>,
+    /// }
+    ///
+    /// let client = Anthropic::from_env()?;
+    /// let contact: Contact = client.messages()
+    ///     .create_structured(
+    ///         MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+    ///             .user("Reach Jane Doe at jane@example.com")
+    ///             .build(),
+    ///         2,
+    ///     )
+    ///     .await?;
+    /// ```
+    #[cfg(feature = "schemars")]
+    pub async fn create_structured<T>(&self, mut params: MessageCreateParams, max_retries: u32) -> Result<T>
+    where
+        T: schemars::JsonSchema + serde::de::DeserializeOwned,
+    {
+        let tool = crate::types::Tool::for_type::<T>();
+        let name = tool.name.clone();
+        params.tools.get_or_insert_with(Vec::new).push(tool.clone().into());
+        params.tool_choice = Some(crate::types::ToolChoice::tool(&name));
+        
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let message = self.create(params.clone()).await?;
+            let tool_use = message.content.iter().find_map(|block| match block {
+                ContentBlock::ToolUse { id, name: tool_name, input } if *tool_name == name => Some((id, input)),
+                _ => None,
+            });
+            
+            // Schema violations come with JSON pointers, and cover constraints serde doesn't check
+            let (tool_use_id, error) = match tool_use {
+                Some((id, input)) => match tool.validate_input(input) {
+                    Err(e) => {
+                        let violations: Vec<String> = e.violations().iter().map(ToString::to_string).collect();
+                        (Some(id.clone()), violations.join("; "))
+                    }
+                    Ok(()) => match serde_json::from_value::<T>(input.clone()) {
+                        Ok(value) => return Ok(value),
+                        Err(e) => (Some(id.clone()), e.to_string()),
+                    },
+                },
+                None => (None, format!("the response did not call the `{}` tool", name)),
+            };
+            if attempts > max_retries {
+                return Err(AnthropicError::StructuredOutput { message: error, attempts });
+            }
+            
+            // Show Claude what was wrong and ask for a corrected call
+            let feedback = format!("Invalid input: {}. Call `{}` again with input matching its schema.", error, name);
+            params.messages.push(message.into_param());
+            params.messages.push(MessageParam {
+                role: Role::User,
+                content: match tool_use_id {
+                    Some(tool_use_id) => MessageContent::Blocks(vec![ContentBlockParam::ToolResult {
+                        tool_use_id,
+                        content: Some(MessageContent::Text(feedback)),
+                        is_error: Some(true),
+                        cache_control: None,
+                    }]),
+                    None => MessageContent::Text(feedback),
+                },
+            });
+        }
+    }
+    
+    /// Resend paused turns until they complete or the continuation limit is reached
+    async fn create_continuing(&self, mut params: MessageCreateParams, max_continuations: u32) -> Result<Message> {
+        let mut message = self.send(&params).await?;
+        let mut content = message.content.clone();
+        let mut usage = message.usage.clone();
+        let mut continuations = 0;
+        
+        while message.stop_reason == Some(StopReason::PauseTurn) && continuations < max_continuations {
+            // The paused content goes back as the trailing assistant message
+            let paused = MessageParam {
+                role: Role::Assistant,
+                content: MessageContent::Blocks(content.iter().map(ContentBlockParam::from).collect()),
+            };
+            if continuations == 0 {
+                params.messages.push(paused);
+            } else if let Some(last) = params.messages.last_mut() {
+                *last = paused;
+            }
+            
+            message = self.send(&params).await?;
+            content.extend(message.content.iter().cloned());
+            usage.accumulate(&message.usage);
+            continuations += 1;
+        }
+        
+        message.content = content;
+        message.usage = usage;
+        Ok(message)
+    }
+    
+    /// Create a message, continuing it while it stops at `max_tokens`
+    ///
+    /// Each continuation sends the output so far back as an assistant prefill,
+    /// so Claude picks up mid-sentence. Text is joined into a single block and
+    /// usage is summed across requests. Continuation stops at the limits in
+    /// `limits`, or when the output ends in something other than text (such as
+    /// a truncated tool call); the returned message then still stops with
+    /// [`StopReason::MaxTokens`].
+    ///
+    /// The API doesn't accept prefills with extended thinking enabled, so with
+    /// [`ThinkingConfig::Enabled`] no continuation is sent and the first,
+    /// truncated response is returned as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use anthropic_sdk::{Anthropic, ContinuationLimits, types::MessageCreateBuilder};
+    ///
+    /// let client = Anthropic::from_env()?;
+    /// let message = client.messages()
+    ///     .create_until_complete(
+    ///         MessageCreateBuilder::new("claude-3-5-sonnet-latest", 4096)
+    ///             .user("Write a detailed design document for a URL shortener")
+    ///             .build(),
+    ///         ContinuationLimits::new(3).max_output_tokens(12_000),
+    ///     )
+    ///     .await?;
+    /// println!("{}", message.text());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_until_complete(&self, params: MessageCreateParams, limits: ContinuationLimits) -> Result<Message> {
+        if self.validate_requests {
+            params.validate()?;
+        }
+        let mut continuation = Continuation::new(params, limits);
+        loop {
+            let message = self.send(&continuation.params).await?;
+            if !continuation.record(message) {
+                return Ok(continuation.finish());
+            }
+        }
+    }
+
+    /// Streaming version of [`create_until_complete`](Self::create_until_complete)
+    ///
+    /// `on_event` sees the events of every request in turn, so a new
+    /// `message_start` marks each continuation. Returns the combined message.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use anthropic_sdk::{Anthropic, ContentBlockDelta, ContinuationLimits, MessageStreamEvent};
+    /// use anthropic_sdk::types::MessageCreateBuilder;
+    ///
+    /// let client = Anthropic::from_env()?;
+    /// let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 4096)
+    ///     .user("Write a long story")
+    ///     .build();
+    /// let message = client.messages()
+    ///     .stream_until_complete(params, ContinuationLimits::default(), |event| {
+    ///         if let MessageStreamEvent::ContentBlockDelta { delta: ContentBlockDelta::TextDelta { text }, .. } = event {
+    ///             print!("{}", text);
+    ///         }
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stream_until_complete<F>(
+        &self,
+        params: MessageCreateParams,
+        limits: ContinuationLimits,
+        mut on_event: F,
+    ) -> Result<Message>
+    where
+        F: FnMut(&crate::types::MessageStreamEvent),
+    {
+        use futures::StreamExt;
+
+        if self.validate_requests {
+            params.validate()?;
+        }
+        let mut continuation = Continuation::new(params, limits);
+        loop {
+            let mut stream = self.create_stream(continuation.params.clone()).await?;
+            while let Some(event) = stream.next().await {
+                on_event(&event?);
+            }
+            if !continuation.record(stream.final_message().await?) {
+                return Ok(continuation.finish());
+            }
+        }
+    }
+
+    async fn send(&self, params: &MessageCreateParams) -> Result<Message> {
         let url = self.client.http_client().build_url("/v1/messages");
         
-        let request = self.client.http_client()
+        let mut request = self.client.http_client()
             .post(&url)
-            .json(&params)
+            .json(params);
+        let betas = params.required_betas();
+        if !betas.is_empty() {
+            request = request.header("anthropic-beta", betas.join(","));
+        }
+        let request = request
             .build()
             .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
         
@@ -60,6 +332,48 @@ impl<'a> MessagesResource<'a> {
         Ok(message)
     }
     
+    /// Count the input tokens a request would use, without creating a message
+    ///
+    /// Accepts [`MessageCreateParams`] or [`MessageCountTokensParams`]; system
+    /// prompts, tools, documents, images and thinking are all counted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use anthropic_sdk::{Anthropic, types::MessageCreateBuilder};
+    ///
+    /// let client = Anthropic::from_env()?;
+    /// let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+    ///     .user("Hello, Claude!")
+    ///     .build();
+    ///
+    /// let count = client.messages().count_tokens(&params).await?;
+    /// println!("Input tokens: {}", count.input_tokens);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn count_tokens(&self, params: impl Into<MessageCountTokensParams>) -> Result<MessageTokensCount> {
+        let params = params.into();
+        let url = self.client.http_client().build_url("/v1/messages/count_tokens");
+        
+        let mut request = self.client.http_client()
+            .post(&url)
+            .json(&params);
+        let betas = params.required_betas();
+        if !betas.is_empty() {
+            request = request.header("anthropic-beta", betas.join(","));
+        }
+        let request = request
+            .build()
+            .map_err(|e| AnthropicError::Connection { message: e.to_string() })?;
+        
+        let response = self.client.http_client().send(request).await?;
+        
+        response.json().await
+            .map_err(|e| AnthropicError::Connection { message: e.to_string() })
+    }
+    
     /// Create a streaming message with Claude
     /// 
     /// Send a message request and receive a real-time stream of the response.
@@ -92,26 +406,21 @@ impl<'a> MessagesResource<'a> {
     /// }
     /// ```
     pub async fn create_stream(&self, mut params: MessageCreateParams) -> Result<MessageStream> {
+        if self.validate_requests {
+            params.validate()?;
+        }
+        
         // Ensure streaming is enabled
         params.stream = Some(true);
         
-        // Create authorization header - use Bearer for most cases including custom gateways
-        let auth_header = format!("Bearer {}", self.client.config().api_key);
-        
-        // Build the streaming request with proper authentication
-        let stream_builder = StreamRequestBuilder::new(
-            self.client.http_client().client().clone(),
-            self.client.config().base_url.clone(),
-        )
-        .header("Authorization", &auth_header)
-        .header("Content-Type", "application/json")
-        .header("anthropic-version", "2023-06-01")
-        .config(StreamConfig::default());
-        
-        // Make the streaming request to get the real HTTP stream
-        let http_stream = stream_builder
-            .post_stream("v1/messages", &params)
-            .await?;
+        // Make the streaming request through the shared HTTP client
+        let mut builder = StreamRequestBuilder::new(self.client.http_client().clone())
+            .config(StreamConfig::default());
+        let betas = params.required_betas();
+        if !betas.is_empty() {
+            builder = builder.header("anthropic-beta", &betas.join(","));
+        }
+        let http_stream = builder.post_stream("v1/messages", &params).await?;
         
         // Create MessageStream that processes the real HTTP stream events
         let message_stream = MessageStream::from_http_stream(http_stream)?;
@@ -177,6 +486,100 @@ impl<'a> MessagesResource<'a> {
     }
 }
 
+/// Accumulates a response across `max_tokens` continuations
+struct Continuation {
+    params: MessageCreateParams,
+    limits: ContinuationLimits,
+    /// Length of the conversation before any continuation prefill
+    history_len: usize,
+    /// A prefill supplied by the caller, which the response continues
+    prefill: Vec<ContentBlockParam>,
+    max_tokens: u32,
+    message: Option<Message>,
+    continuations: u32,
+}
+
+impl Continuation {
+    fn new(params: MessageCreateParams, limits: ContinuationLimits) -> Self {
+        let mut history_len = params.messages.len();
+        let mut prefill = Vec::new();
+        if let Some(last) = params.messages.last().filter(|message| message.role == Role::Assistant) {
+            history_len -= 1;
+            prefill = match &last.content {
+                MessageContent::Text(text) => vec![ContentBlockParam::text(text.clone())],
+                MessageContent::Blocks(blocks) => blocks.clone(),
+            };
+        }
+        let max_tokens = params.max_tokens;
+        Self { params, limits, history_len, prefill, max_tokens, message: None, continuations: 0 }
+    }
+
+    /// Add a response; returns whether another request should be sent
+    fn record(&mut self, message: Message) -> bool {
+        let combined = match self.message.take() {
+            None => message,
+            Some(mut combined) => {
+                let mut content = message.content.into_iter().peekable();
+                if let Some(ContentBlock::Text { text: previous, citations: previous_citations }) =
+                    combined.content.last_mut()
+                {
+                    if let Some(ContentBlock::Text { text, citations }) =
+                        content.next_if(|block| matches!(block, ContentBlock::Text { .. }))
+                    {
+                        // The prefill was sent without trailing whitespace, which Claude writes again
+                        previous.truncate(previous.trim_end().len());
+                        previous.push_str(&text);
+                        if let Some(citations) = citations {
+                            previous_citations.get_or_insert_with(Vec::new).extend(citations);
+                        }
+                    }
+                }
+                combined.content.extend(content);
+                combined.usage.accumulate(&message.usage);
+                combined.stop_reason = message.stop_reason;
+                combined.stop_sequence = message.stop_sequence;
+                combined.request_id = message.request_id;
+                combined
+            }
+        };
+        let message = self.message.insert(combined);
+
+        let remaining = match self.limits.max_output_tokens {
+            Some(limit) => limit.saturating_sub(message.usage.output_tokens),
+            None => self.max_tokens,
+        };
+        let ends_in_text = matches!(message.content.last(), Some(ContentBlock::Text { .. }));
+        // Prefilled responses are rejected while extended thinking is on
+        let thinking = matches!(self.params.thinking, Some(ThinkingConfig::Enabled { .. }));
+        if message.stop_reason != Some(StopReason::MaxTokens)
+            || !ends_in_text
+            || thinking
+            || self.continuations >= self.limits.max_continuations
+            || remaining == 0
+        {
+            return false;
+        }
+
+        let mut prefill = self.prefill.clone();
+        prefill.extend(message.content.iter().map(ContentBlockParam::from));
+        // The API rejects prefills ending in whitespace
+        if let Some(ContentBlockParam::Text { text, .. }) = prefill.last_mut() {
+            text.truncate(text.trim_end().len());
+        }
+        prefill.retain(|block| !matches!(block, ContentBlockParam::Text { text, .. } if text.is_empty()));
+
+        self.params.messages.truncate(self.history_len);
+        self.params.messages.push(MessageParam { role: Role::Assistant, content: MessageContent::Blocks(prefill) });
+        self.params.max_tokens = self.max_tokens.min(remaining);
+        self.continuations += 1;
+        true
+    }
+
+    fn finish(self) -> Message {
+        self.message.expect("at least one response was recorded")
+    }
+}
+
 /// A message builder with a client reference for sending requests
 pub struct MessageCreateBuilderWithClient<'a> {
     resource: &'a MessagesResource<'a>,
@@ -203,11 +606,23 @@ impl<'a> MessageCreateBuilderWithClient<'a> {
     }
     
     /// Set the system prompt
-    pub fn system(mut self, system: impl Into<String>) -> Self {
+    pub fn system(mut self, system: impl Into<SystemPrompt>) -> Self {
         self.builder = self.builder.system(system);
         self
     }
     
+    /// Set the system prompt as a single block marked as a cache breakpoint
+    pub fn system_cached(mut self, system: impl Into<String>) -> Self {
+        self.builder = self.builder.system_cached(system);
+        self
+    }
+    
+    /// Mark the last block of the last message as a cache breakpoint
+    pub fn cache_last_message(mut self) -> Self {
+        self.builder = self.builder.cache_last_message();
+        self
+    }
+    
     /// Set the temperature
     pub fn temperature(mut self, temperature: f32) -> Self {
         self.builder = self.builder.temperature(temperature);
@@ -237,7 +652,55 @@ impl<'a> MessageCreateBuilderWithClient<'a> {
         self.builder = self.builder.stream(stream);
         self
     }
-    
+
+    /// Set the extended thinking configuration
+    pub fn thinking(mut self, thinking: ThinkingConfig) -> Self {
+        self.builder = self.builder.thinking(thinking);
+        self
+    }
+
+    /// Enable extended thinking with the given token budget
+    pub fn enable_thinking(mut self, budget_tokens: u32) -> Self {
+        self.builder = self.builder.enable_thinking(budget_tokens);
+        self
+    }
+
+    /// Set metadata
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.builder = self.builder.metadata(metadata);
+        self
+    }
+
+    /// Identify the end user for abuse detection
+    pub fn user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.builder = self.builder.user_id(user_id);
+        self
+    }
+
+    /// Choose whether the request may use priority capacity
+    pub fn service_tier(mut self, service_tier: ServiceTierPreference) -> Self {
+        self.builder = self.builder.service_tier(service_tier);
+        self
+    }
+
+    /// Reuse a code execution container from an earlier response
+    pub fn container(mut self, container_id: impl Into<String>) -> Self {
+        self.builder = self.builder.container(container_id);
+        self
+    }
+
+    /// Connect a remote MCP server
+    pub fn mcp_server(mut self, server: McpServer) -> Self {
+        self.builder = self.builder.mcp_server(server);
+        self
+    }
+
+    /// Configure automatic context management
+    pub fn context_management(mut self, context_management: ContextManagement) -> Self {
+        self.builder = self.builder.context_management(context_management);
+        self
+    }
+
     /// Send the message request
     pub async fn send(self) -> Result<Message> {
         self.resource.create(self.builder.build()).await
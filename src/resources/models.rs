@@ -57,21 +57,15 @@ impl<'a> ModelsResource<'a> {
             }
         }
         
-        let url = format!("{}/v1/models", self.client.config().base_url);
-        let response = self.client.http_client()
+        let url = self.client.http_client().build_url("/v1/models");
+        let request = self.client.http_client()
             .get(&url)
             .query(&query_params)
-            .send()
-            .await?;
+            .build()?;
+        let response = self.client.http_client().send(request).await?;
             
-        if response.status().is_success() {
-            let model_list: ModelList = response.json().await?;
-            Ok(model_list)
-        } else {
-            let status = response.status().as_u16();
-            let error_text = response.text().await?;
-            Err(AnthropicError::from_status(status, error_text))
-        }
+        let model_list: ModelList = response.json().await?;
+        Ok(model_list)
     }
 
     /// Get a specific model by ID or alias
@@ -95,20 +89,14 @@ impl<'a> ModelsResource<'a> {
     /// # }
     /// ```
     pub async fn get(&self, model_id: &str) -> Result<ModelObject> {
-        let url = format!("{}/v1/models/{}", self.client.config().base_url, model_id);
-        let response = self.client.http_client()
+        let url = self.client.http_client().build_url(&format!("/v1/models/{}", model_id));
+        let request = self.client.http_client()
             .get(&url)
-            .send()
-            .await?;
+            .build()?;
+        let response = self.client.http_client().send(request).await?;
             
-        if response.status().is_success() {
-            let model: ModelObject = response.json().await?;
-            Ok(model)
-        } else {
-            let status = response.status().as_u16();
-            let error_text = response.text().await?;
-            Err(AnthropicError::from_status(status, error_text))
-        }
+        let model: ModelObject = response.json().await?;
+        Ok(model)
     }
 
     /// List models by family (e.g., "claude-3", "claude-3-5")
@@ -0,0 +1,236 @@
+//! Accumulation of stream events into a complete message.
+
+use std::collections::HashMap;
+
+use crate::types::{ContentBlock, ContentBlockDelta, Message, MessageStreamEvent};
+
+/// Builds a [`Message`] from a sequence of stream events.
+///
+/// Text, citations, tool input JSON (including server tool calls), thinking text
+/// and thinking signatures are all accumulated, so the final message matches what a non-streaming request
+/// would have returned.
+///
+/// # Examples
+/// ```rust
+/// use anthropic_sdk::streaming::MessageAccumulator;
+/// use anthropic_sdk::MessageStreamEvent;
+///
+/// let mut accumulator = MessageAccumulator::new();
+/// accumulator.apply(&MessageStreamEvent::MessageStop);
+/// assert!(accumulator.message().is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageAccumulator {
+    message: Option<Message>,
+    partial_json: HashMap<usize, String>,
+}
+
+impl MessageAccumulator {
+    /// Create an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a stream event to the message snapshot.
+    pub fn apply(&mut self, event: &MessageStreamEvent) {
+        match event {
+            MessageStreamEvent::MessageStart { message } => {
+                self.message = Some(message.clone());
+                self.partial_json.clear();
+            }
+            MessageStreamEvent::ContentBlockStart { content_block, index } => {
+                if let Some(message) = self.message.as_mut() {
+                    while message.content.len() <= *index {
+                        message.content.push(ContentBlock::Text { text: String::new(), citations: None });
+                    }
+                    message.content[*index] = content_block.clone();
+                }
+            }
+            MessageStreamEvent::ContentBlockDelta { delta, index } => {
+                let Some(block) = self.message.as_mut().and_then(|m| m.content.get_mut(*index)) else {
+                    return;
+                };
+                match (block, delta) {
+                    (ContentBlock::Text { text, .. }, ContentBlockDelta::TextDelta { text: delta }) => {
+                        text.push_str(delta);
+                    }
+                    (ContentBlock::Text { citations, .. }, ContentBlockDelta::CitationsDelta { citation }) => {
+                        citations.get_or_insert_with(Vec::new).push(citation.clone());
+                    }
+                    (
+                        ContentBlock::ToolUse { input, .. } | ContentBlock::ServerToolUse { input, .. },
+                        ContentBlockDelta::InputJsonDelta { partial_json },
+                    ) => {
+                        let buffer = self.partial_json.entry(*index).or_default();
+                        buffer.push_str(partial_json);
+                        // Keep the input current whenever the buffer is valid JSON
+                        if let Ok(value) = serde_json::from_str(buffer) {
+                            *input = value;
+                        }
+                    }
+                    (ContentBlock::Thinking { thinking, .. }, ContentBlockDelta::ThinkingDelta { thinking: delta }) => {
+                        thinking.push_str(delta);
+                    }
+                    (ContentBlock::Thinking { signature, .. }, ContentBlockDelta::SignatureDelta { signature: delta }) => {
+                        *signature = delta.clone();
+                    }
+                    _ => {}
+                }
+            }
+            MessageStreamEvent::ContentBlockStop { index } => {
+                self.partial_json.remove(index);
+            }
+            MessageStreamEvent::MessageDelta { delta, usage } => {
+                if let Some(message) = self.message.as_mut() {
+                    if let Some(stop_reason) = &delta.stop_reason {
+                        message.stop_reason = Some(stop_reason.clone());
+                    }
+                    if let Some(stop_sequence) = &delta.stop_sequence {
+                        message.stop_sequence = Some(stop_sequence.clone());
+                    }
+                    message.usage.output_tokens = usage.output_tokens;
+                    if let Some(input_tokens) = usage.input_tokens {
+                        message.usage.input_tokens = input_tokens;
+                    }
+                    if usage.cache_creation_input_tokens.is_some() {
+                        message.usage.cache_creation_input_tokens = usage.cache_creation_input_tokens;
+                    }
+                    if usage.cache_read_input_tokens.is_some() {
+                        message.usage.cache_read_input_tokens = usage.cache_read_input_tokens;
+                    }
+                    if usage.server_tool_use.is_some() {
+                        message.usage.server_tool_use = usage.server_tool_use.clone();
+                    }
+                }
+            }
+            MessageStreamEvent::MessageStop | MessageStreamEvent::Unknown(_) => {}
+        }
+    }
+
+    /// The message accumulated so far, if the stream has started.
+    pub fn message(&self) -> Option<&Message> {
+        self.message.as_ref()
+    }
+
+    /// Consume the accumulator and return the message.
+    pub fn into_message(self) -> Option<Message> {
+        self.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{fixtures, message_events};
+    use crate::types::{StopReason, TextCitation};
+    use serde_json::json;
+
+    fn accumulate(message: &Message) -> Message {
+        let mut accumulator = MessageAccumulator::new();
+        for (_, data) in message_events(message) {
+            let event: MessageStreamEvent = serde_json::from_value(data).unwrap();
+            accumulator.apply(&event);
+        }
+        accumulator.into_message().unwrap()
+    }
+
+    #[test]
+    fn test_accumulates_text_and_usage() {
+        let original = fixtures::text_message("Hello there world");
+        let message = accumulate(&original);
+        assert_eq!(message.content, original.content);
+        assert_eq!(message.stop_reason, Some(StopReason::EndTurn));
+        assert_eq!(message.usage.output_tokens, original.usage.output_tokens);
+    }
+
+    #[test]
+    fn test_accumulates_split_tool_input() {
+        let mut accumulator = MessageAccumulator::new();
+        let start = fixtures::text_message("");
+        let events = vec![
+            json!({"type": "message_start", "message": start}),
+            json!({"type": "content_block_start", "index": 0,
+                   "content_block": {"type": "tool_use", "id": "toolu_1", "name": "calc", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 0,
+                   "delta": {"type": "input_json_delta", "partial_json": "{\"expr\": \"1"}}),
+            json!({"type": "content_block_delta", "index": 0,
+                   "delta": {"type": "input_json_delta", "partial_json": " + 1\"}"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+        ];
+        for event in events {
+            accumulator.apply(&serde_json::from_value(event).unwrap());
+        }
+
+        match &accumulator.message().unwrap().content[0] {
+            ContentBlock::ToolUse { input, .. } => assert_eq!(input, &json!({"expr": "1 + 1"})),
+            other => panic!("unexpected block: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_accumulates_thinking_and_signature() {
+        let original = fixtures::message(
+            vec![
+                ContentBlock::Thinking {
+                    thinking: "Let me think about this".to_string(),
+                    signature: "sig_abc".to_string(),
+                },
+                ContentBlock::RedactedThinking { data: "opaque".to_string() },
+                ContentBlock::Text { text: "The answer".to_string(), citations: None },
+            ],
+            StopReason::EndTurn,
+        );
+
+        let message = accumulate(&original);
+        assert_eq!(message.content, original.content);
+    }
+
+    #[test]
+    fn test_accumulates_server_tool_blocks() {
+        let original = fixtures::message(
+            vec![
+                ContentBlock::ServerToolUse {
+                    id: "srvtoolu_1".to_string(),
+                    name: "web_search".to_string(),
+                    input: json!({"query": "rust release"}),
+                },
+                ContentBlock::WebSearchToolResult {
+                    tool_use_id: "srvtoolu_1".to_string(),
+                    content: serde_json::from_value(json!([{
+                        "type": "web_search_result",
+                        "url": "https://blog.rust-lang.org",
+                        "title": "Rust Blog",
+                        "encrypted_content": "enc",
+                    }]))
+                    .unwrap(),
+                },
+                ContentBlock::Text { text: "Rust shipped".to_string(), citations: None },
+            ],
+            StopReason::EndTurn,
+        );
+
+        let message = accumulate(&original);
+        assert_eq!(message.content, original.content);
+    }
+
+    #[test]
+    fn test_accumulates_citations() {
+        let citation = TextCitation::CharLocation {
+            cited_text: "The grass is green.".to_string(),
+            document_index: 0,
+            document_title: Some("Facts".to_string()),
+            start_char_index: 0,
+            end_char_index: 19,
+        };
+        let original = fixtures::message(
+            vec![ContentBlock::Text {
+                text: "The grass is green".to_string(),
+                citations: Some(vec![citation]),
+            }],
+            StopReason::EndTurn,
+        );
+
+        let message = accumulate(&original);
+        assert_eq!(message.content, original.content);
+    }
+}
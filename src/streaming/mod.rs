@@ -4,6 +4,7 @@
 //! from the Anthropic API, accumulates messages from incremental updates, and provides
 //! an event-driven API for processing streaming responses.
 
+pub mod accumulator;
 pub mod events;
 
 use std::collections::HashMap;
@@ -14,10 +15,11 @@ use tokio::sync::{broadcast, oneshot};
 use tokio_stream::wrappers::BroadcastStream;
 
 use crate::types::{
-    Message, MessageStreamEvent, ContentBlock, ContentBlockDelta, 
+    Message, MessageStreamEvent, ContentBlockDelta, 
     AnthropicError, Result
 };
 
+pub use self::accumulator::MessageAccumulator;
 use self::events::{EventHandler, EventType};
 
 /// A streaming response from the Anthropic API.
@@ -80,13 +82,16 @@ use self::events::{EventHandler, EventType};
 #[pin_project]
 pub struct MessageStream {
     /// Current accumulated message snapshot
-    current_message: Arc<Mutex<Option<Message>>>,
+    current_message: Arc<Mutex<MessageAccumulator>>,
     
     /// Event handlers for different event types
     event_handlers: Arc<Mutex<HashMap<EventType, Vec<EventHandler>>>>,
     
     /// Broadcast channel for distributing events to handlers
-    event_sender: broadcast::Sender<MessageStreamEvent>,
+    ///
+    /// `None` when a background task owns the sender, so the event stream
+    /// terminates once that task finishes.
+    event_sender: Option<broadcast::Sender<MessageStreamEvent>>,
     
     /// Stream for events from the underlying HTTP stream
     #[pin]
@@ -119,9 +124,9 @@ impl MessageStream {
         let (completion_sender, completion_receiver) = oneshot::channel();
         
         Self {
-            current_message: Arc::new(Mutex::new(None)),
+            current_message: Arc::new(Mutex::new(MessageAccumulator::new())),
             event_handlers: Arc::new(Mutex::new(HashMap::new())),
-            event_sender,
+            event_sender: Some(event_sender),
             event_stream: BroadcastStream::new(event_receiver),
             completion_sender: Some(completion_sender),
             completion_receiver,
@@ -141,7 +146,7 @@ impl MessageStream {
         let (event_sender, event_receiver) = broadcast::channel(1000);
         let (completion_sender, completion_receiver) = oneshot::channel();
         
-        let current_message = Arc::new(Mutex::new(None));
+        let current_message = Arc::new(Mutex::new(MessageAccumulator::new()));
         let ended = Arc::new(Mutex::new(false));
         let errored = Arc::new(Mutex::new(false));
         let request_id = http_stream.request_id().map(|s| s.to_string());
@@ -150,97 +155,28 @@ impl MessageStream {
         let current_message_clone = current_message.clone();
         let ended_clone = ended.clone();
         let errored_clone = errored.clone();
-        let event_sender_clone = event_sender.clone();
+        let event_sender_clone = event_sender;
         
         // Spawn task to process HTTP stream events
         tokio::spawn(async move {
             use futures::StreamExt;
-            let mut final_message: Option<crate::types::Message> = None;
             
             while let Some(event_result) = http_stream.next().await {
                 match event_result {
                     Ok(event) => {
                         // Update current message state
-                        match &event {
-                            crate::types::MessageStreamEvent::MessageStart { message } => {
-                                *current_message_clone.lock().unwrap() = Some(message.clone());
-                                final_message = Some(message.clone());
-                            }
-                            crate::types::MessageStreamEvent::ContentBlockStart { content_block, index } => {
-                                if let Some(ref mut msg) = *current_message_clone.lock().unwrap() {
-                                    while msg.content.len() <= *index {
-                                        msg.content.push(crate::types::ContentBlock::Text { text: String::new() });
-                                    }
-                                    msg.content[*index] = content_block.clone();
-                                }
-                                if let Some(ref mut msg) = final_message.as_mut() {
-                                    while msg.content.len() <= *index {
-                                        msg.content.push(crate::types::ContentBlock::Text { text: String::new() });
-                                    }
-                                    msg.content[*index] = content_block.clone();
-                                }
-                            }
-                            crate::types::MessageStreamEvent::ContentBlockDelta { delta, index } => {
-                                if let Some(ref mut msg) = *current_message_clone.lock().unwrap() {
-                                    if let Some(content_block) = msg.content.get_mut(*index) {
-                                        if let (crate::types::ContentBlock::Text { text }, 
-                                               crate::types::ContentBlockDelta::TextDelta { text: delta_text }) = 
-                                            (content_block, delta) {
-                                            text.push_str(delta_text);
-                                        }
-                                    }
-                                }
-                                if let Some(ref mut msg) = final_message.as_mut() {
-                                    if let Some(content_block) = msg.content.get_mut(*index) {
-                                        if let (crate::types::ContentBlock::Text { text }, 
-                                               crate::types::ContentBlockDelta::TextDelta { text: delta_text }) = 
-                                            (content_block, delta) {
-                                            text.push_str(delta_text);
-                                        }
-                                    }
-                                }
-                            }
-                            crate::types::MessageStreamEvent::MessageDelta { delta, usage } => {
-                                if let Some(ref mut msg) = *current_message_clone.lock().unwrap() {
-                                    if let Some(stop_reason) = &delta.stop_reason {
-                                        msg.stop_reason = Some(stop_reason.clone());
-                                    }
-                                    if let Some(stop_sequence) = &delta.stop_sequence {
-                                        msg.stop_sequence = Some(stop_sequence.clone());
-                                    }
-                                    msg.usage.output_tokens = usage.output_tokens;
-                                    if let Some(input_tokens) = usage.input_tokens {
-                                        msg.usage.input_tokens = input_tokens;
-                                    }
-                                }
-                                if let Some(ref mut msg) = final_message.as_mut() {
-                                    if let Some(stop_reason) = &delta.stop_reason {
-                                        msg.stop_reason = Some(stop_reason.clone());
-                                    }
-                                    if let Some(stop_sequence) = &delta.stop_sequence {
-                                        msg.stop_sequence = Some(stop_sequence.clone());
-                                    }
-                                    msg.usage.output_tokens = usage.output_tokens;
-                                    if let Some(input_tokens) = usage.input_tokens {
-                                        msg.usage.input_tokens = input_tokens;
-                                    }
-                                }
-                            }
-                            crate::types::MessageStreamEvent::MessageStop => {
-                                *ended_clone.lock().unwrap() = true;
-                                // Send the final message
-                                if let Some(message) = final_message.clone() {
-                                    let _ = completion_sender.send(Ok(message));
-                                } else {
-                                    let _ = completion_sender.send(Err(crate::types::AnthropicError::StreamError(
-                                        "Stream ended without message".to_string()
-                                    )));
-                                }
-                                // Send final event and break
-                                let _ = event_sender_clone.send(event);
-                                break;
-                            }
-                            _ => {}
+                        current_message_clone.lock().unwrap().apply(&event);
+                        
+                        if let MessageStreamEvent::MessageStop = event {
+                            *ended_clone.lock().unwrap() = true;
+                            // Send the final message
+                            let result = current_message_clone.lock().unwrap().message().cloned().ok_or_else(|| {
+                                AnthropicError::StreamError("Stream ended without message".to_string())
+                            });
+                            let _ = completion_sender.send(result);
+                            // Send final event and break
+                            let _ = event_sender_clone.send(event);
+                            break;
                         }
                         
                         // Send event to broadcast channel for callbacks
@@ -258,7 +194,7 @@ impl MessageStream {
         Ok(Self {
             current_message,
             event_handlers: Arc::new(Mutex::new(HashMap::new())),
-            event_sender,
+            event_sender: None,
             event_stream: BroadcastStream::new(event_receiver),
             completion_sender: None, // Already consumed by the task
             completion_receiver,
@@ -442,7 +378,7 @@ impl MessageStream {
     ///
     /// Returns `None` if the stream hasn't started or no message has been received yet.
     pub fn current_message(&self) -> Option<Message> {
-        self.current_message.lock().unwrap().clone()
+        self.current_message.lock().unwrap().message().cloned()
     }
     
     /// Check if the stream has ended.
@@ -485,86 +421,33 @@ impl MessageStream {
     #[allow(dead_code)]
     fn process_event(&self, event: MessageStreamEvent) -> Result<()> {
         // Update current message state based on the event
-        match &event {
-            MessageStreamEvent::MessageStart { message } => {
-                *self.current_message.lock().unwrap() = Some(message.clone());
-            }
-            MessageStreamEvent::ContentBlockStart { content_block, index } => {
-                if let Some(ref mut msg) = *self.current_message.lock().unwrap() {
-                    // Ensure the content array is large enough
-                    while msg.content.len() <= *index {
-                        msg.content.push(ContentBlock::Text { text: String::new() });
-                    }
-                    msg.content[*index] = content_block.clone();
-                }
-            }
-            MessageStreamEvent::ContentBlockDelta { delta, index } => {
-                if let Some(ref mut msg) = *self.current_message.lock().unwrap() {
-                    if let Some(content_block) = msg.content.get_mut(*index) {
-                        self.apply_delta(content_block, delta)?;
-                    }
-                }
-            }
-            MessageStreamEvent::MessageDelta { delta, usage } => {
-                if let Some(ref mut msg) = *self.current_message.lock().unwrap() {
-                    if let Some(stop_reason) = &delta.stop_reason {
-                        msg.stop_reason = Some(stop_reason.clone());
-                    }
-                    if let Some(stop_sequence) = &delta.stop_sequence {
-                        msg.stop_sequence = Some(stop_sequence.clone());
-                    }
-                    // Update usage information
-                    msg.usage.output_tokens = usage.output_tokens;
-                    if let Some(input_tokens) = usage.input_tokens {
-                        msg.usage.input_tokens = input_tokens;
-                    }
-                }
-            }
-            MessageStreamEvent::MessageStop => {
-                *self.ended.lock().unwrap() = true;
-            }
-            _ => {}
+        self.current_message.lock().unwrap().apply(&event);
+        if let MessageStreamEvent::MessageStop = event {
+            *self.ended.lock().unwrap() = true;
         }
         
         // Dispatch event to handlers
         self.dispatch_event(&event)?;
         
         // Send event to broadcast channel for async iteration
-        let _ = self.event_sender.send(event);
-        
-        Ok(())
-    }
-    
-    /// Apply a content block delta to update the content.
-    #[allow(dead_code)]
-    fn apply_delta(&self, content_block: &mut ContentBlock, delta: &ContentBlockDelta) -> Result<()> {
-        match (content_block, delta) {
-            (ContentBlock::Text { text }, ContentBlockDelta::TextDelta { text: delta_text }) => {
-                text.push_str(delta_text);
-            }
-            (ContentBlock::ToolUse { input, .. }, ContentBlockDelta::InputJsonDelta { partial_json }) => {
-                // In a real implementation, we'd parse the partial JSON
-                // For now, we'll just store it as-is
-                *input = serde_json::from_str(partial_json)
-                    .unwrap_or_else(|_| serde_json::Value::String(partial_json.clone()));
-            }
-            _ => {
-                // Other delta types would be handled here
-            }
+        if let Some(sender) = &self.event_sender {
+            let _ = sender.send(event);
         }
+        
         Ok(())
     }
     
     /// Dispatch an event to all registered handlers.
     fn dispatch_event(&self, event: &MessageStreamEvent) -> Result<()> {
         let handlers = self.event_handlers.lock().unwrap();
-        let current_message = self.current_message.lock().unwrap();
+        let accumulator = self.current_message.lock().unwrap();
+        let current_message = accumulator.message();
         
         // Dispatch to stream event handlers
         if let Some(stream_handlers) = handlers.get(&EventType::StreamEvent) {
             for handler in stream_handlers {
                 if let EventHandler::StreamEvent(callback) = handler {
-                    if let Some(ref msg) = *current_message {
+                    if let Some(msg) = current_message {
                         callback(event, msg);
                     }
                 }
@@ -579,7 +462,7 @@ impl MessageStream {
                         for handler in text_handlers {
                             if let EventHandler::Text(callback) = handler {
                                 // Get current accumulated text for snapshot
-                                let snapshot = if let Some(ref msg) = *current_message {
+                                let snapshot = if let Some(msg) = current_message {
                                     self.get_accumulated_text(msg)
                                 } else {
                                     String::new()
@@ -600,7 +483,7 @@ impl MessageStream {
                 }
                 
                 // Send final message
-                if let Some(ref msg) = *current_message {
+                if let Some(msg) = current_message {
                     if let Some(final_handlers) = handlers.get(&EventType::FinalMessage) {
                         for handler in final_handlers {
                             if let EventHandler::FinalMessage(callback) = handler {
@@ -618,14 +501,7 @@ impl MessageStream {
     
     /// Get the accumulated text from all text content blocks.
     fn get_accumulated_text(&self, message: &Message) -> String {
-        message.content
-            .iter()
-            .filter_map(|block| match block {
-                ContentBlock::Text { text } => Some(text.as_str()),
-                _ => None,
-            })
-            .collect::<Vec<_>>()
-            .join("")
+        message.text()
     }
 }
 
@@ -665,10 +541,12 @@ mod tests {
     
     // For testing, we'll use a simple helper to create a dummy response
     async fn create_dummy_response() -> reqwest::Response {
-        // Create a simple HTTP client and make a basic request for testing
-        let client = reqwest::Client::new();
-        // Use httpbin.org which provides testing endpoints
-        client.get("https://httpbin.org/status/200")
+        // Use the in-process mock server so the tests run offline
+        let server = crate::testing::MockServer::start()
+            .await
+            .expect("Failed to start mock server");
+        reqwest::Client::new()
+            .get(format!("{}/v1/models", server.url()))
             .send()
             .await
             .expect("Failed to create test response")
@@ -0,0 +1,124 @@
+//! Ready-made API objects for scripting the mock server.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{Duration, Utc};
+use serde_json::Value;
+
+use crate::types::{
+    BatchRequestCounts, BatchStatus, ContentBlock, FileObject, FilePurpose, FileStatus, Message,
+    MessageBatch, ModelObject, Role, StopReason, Usage,
+};
+
+/// Model reported by fixtures when the request does not name one.
+pub const DEFAULT_MODEL: &str = "claude-3-5-sonnet-latest";
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Generate a unique identifier with the given prefix, e.g. `msg_mock_1`.
+pub fn next_id(prefix: &str) -> String {
+    format!("{}_mock_{}", prefix, NEXT_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// A completed assistant message with the given content blocks.
+pub fn message(content: Vec<ContentBlock>, stop_reason: StopReason) -> Message {
+    let output_tokens = content
+        .iter()
+        .map(|block| match block {
+            ContentBlock::Text { text, .. } => text.split_whitespace().count() as u32,
+            _ => 10,
+        })
+        .sum();
+
+    Message {
+        id: next_id("msg"),
+        type_: "message".to_string(),
+        role: Role::Assistant,
+        content,
+        model: DEFAULT_MODEL.to_string(),
+        stop_reason: Some(stop_reason),
+        stop_sequence: None,
+        usage: Usage {
+            input_tokens: 10,
+            output_tokens,
+            cache_creation_input_tokens: None,
+            cache_read_input_tokens: None,
+            server_tool_use: None,
+            service_tier: None,
+        },
+        request_id: None,
+    }
+}
+
+/// An assistant message containing a single text block.
+pub fn text_message(text: impl Into<String>) -> Message {
+    message(vec![ContentBlock::Text { text: text.into(), citations: None }], StopReason::EndTurn)
+}
+
+/// An assistant message requesting a single tool call.
+pub fn tool_use_message(name: impl Into<String>, input: Value) -> Message {
+    message(
+        vec![ContentBlock::ToolUse { id: next_id("toolu"), name: name.into(), input }],
+        StopReason::ToolUse,
+    )
+}
+
+/// A message batch in the given state.
+pub fn batch(status: BatchStatus, total: u32) -> MessageBatch {
+    let now = Utc::now();
+    let completed = status == BatchStatus::Completed;
+
+    MessageBatch {
+        id: next_id("msgbatch"),
+        object_type: "message_batch".to_string(),
+        processing_status: status,
+        request_counts: BatchRequestCounts {
+            total,
+            completed: if completed { total } else { 0 },
+            failed: 0,
+        },
+        created_at: now,
+        expires_at: now + Duration::hours(24),
+        ended_at: completed.then_some(now),
+        input_file_id: next_id("file"),
+        output_file_id: completed.then(|| next_id("file")),
+        error_file_id: None,
+        metadata: HashMap::new(),
+    }
+}
+
+/// An uploaded, processed file.
+pub fn file_object(filename: impl Into<String>, content_type: impl Into<String>, size_bytes: u64) -> FileObject {
+    FileObject {
+        id: next_id("file"),
+        object_type: "file".to_string(),
+        filename: filename.into(),
+        size_bytes,
+        content_type: content_type.into(),
+        purpose: FilePurpose::Upload,
+        created_at: Utc::now(),
+        expires_at: None,
+        status: FileStatus::Processed,
+        metadata: HashMap::new(),
+    }
+}
+
+/// A model listing entry.
+pub fn model(id: impl Into<String>, display_name: impl Into<String>) -> ModelObject {
+    ModelObject {
+        id: id.into(),
+        display_name: display_name.into(),
+        created_at: Utc::now(),
+        object_type: "model".to_string(),
+    }
+}
+
+/// The models returned by `GET /v1/models` unless a response is scripted.
+pub fn default_models() -> Vec<ModelObject> {
+    vec![
+        model("claude-3-5-sonnet-20241022", "Claude 3.5 Sonnet"),
+        model("claude-3-5-haiku-20241022", "Claude 3.5 Haiku"),
+        model("claude-3-opus-20240229", "Claude 3 Opus"),
+    ]
+}
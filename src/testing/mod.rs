@@ -0,0 +1,550 @@
+//! In-process mock of the Anthropic API for offline tests.
+//!
+//! [`MockServer`] listens on a random localhost port and speaks enough HTTP to
+//! serve the SDK's endpoints: `/v1/messages` (JSON and SSE), token counting,
+//! message batches, files and models. Responses can be scripted per route, errors injected
+//! (429, 529, slow responses, mid-stream disconnects) and every request the
+//! client sent is captured for assertions.
+//!
+//! Enabled with the `testing` feature.
+//!
+//! ```rust,no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use anthropic_sdk::testing::{MockServer, MockResponse};
+//! use anthropic_sdk::MessageCreateBuilder;
+//!
+//! let server = MockServer::start().await?;
+//! server.enqueue_message(MockResponse::text("Hi!"));
+//!
+//! let client = server.client()?;
+//! let message = client
+//!     .messages()
+//!     .create(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hello").build())
+//!     .await?;
+//!
+//! let request = server.last_request().unwrap();
+//! assert_eq!(request.path, "/v1/messages");
+//! assert_eq!(request.json()["messages"][0]["content"], "Hello");
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod fixtures;
+mod response;
+mod server;
+
+pub use response::{message_events, MockResponse};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncRead, AsyncWrite, BufReader};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::types::{
+    AnthropicError, BatchResult, BatchStatus, FileList, FileObject, MessageBatch, ModelList, Result,
+};
+use crate::{Anthropic, ClientConfig};
+
+use server::RawRequest;
+
+/// API key used by clients created through [`MockServer::client`].
+pub const MOCK_API_KEY: &str = "sk-ant-mock-key";
+
+/// A request received by the mock server.
+#[derive(Debug, Clone)]
+pub struct CapturedRequest {
+    /// HTTP method, e.g. `POST`
+    pub method: String,
+
+    /// Request path without the query string
+    pub path: String,
+
+    /// Raw query string, if any
+    pub query: Option<String>,
+
+    /// Request headers with lowercase names
+    pub headers: Vec<(String, String)>,
+
+    /// Raw request body
+    pub body: Vec<u8>,
+}
+
+impl CapturedRequest {
+    /// Get a header value by (case-insensitive) name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Parse the body as JSON, returning `Value::Null` if it is not valid JSON.
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or(Value::Null)
+    }
+
+    /// Get the body as (lossy) UTF-8 text.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Look up a query parameter.
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        self.query.as_deref()?.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key == name).then(|| value.to_string())
+        })
+    }
+
+    fn matches(&self, method: &str, pattern: &str) -> bool {
+        self.method.eq_ignore_ascii_case(method) && path_matches(pattern, &self.path)
+    }
+}
+
+impl From<RawRequest> for CapturedRequest {
+    fn from(raw: RawRequest) -> Self {
+        Self {
+            method: raw.method,
+            path: raw.path,
+            query: raw.query,
+            headers: raw.headers,
+            body: raw.body,
+        }
+    }
+}
+
+/// A scripted response waiting for a matching request.
+#[derive(Debug)]
+struct Scripted {
+    method: String,
+    path: String,
+    response: MockResponse,
+}
+
+/// Shared server state.
+#[derive(Debug, Default)]
+struct State {
+    scripted: Vec<Scripted>,
+    requests: Vec<CapturedRequest>,
+    batches: Vec<MessageBatch>,
+    files: Vec<(FileObject, Vec<u8>)>,
+    request_count: u64,
+}
+
+/// An in-process mock of the Anthropic API.
+///
+/// Scripted responses are consumed first-in first-out per route. Requests
+/// without a scripted response get a sensible default built from the SDK's
+/// own types, so a test only has to script what it cares about. The server
+/// shuts down when dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    url: String,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start a mock server on a random localhost port.
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.map_err(|e| AnthropicError::Connection {
+            message: format!("Failed to bind mock server: {}", e),
+        })?;
+        let addr = listener.local_addr().map_err(|e| AnthropicError::Connection {
+            message: format!("Failed to read mock server address: {}", e),
+        })?;
+
+        let state = Arc::new(Mutex::new(State::default()));
+        let accept_state = state.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_connection(stream, accept_state.clone()));
+            }
+        });
+
+        Ok(Self { url: format!("http://{}", addr), state, handle })
+    }
+
+    /// Start a mock server listening on a Unix domain socket at `path`.
+    ///
+    /// Any existing file at `path` is replaced. [`MockServer::url`] returns a
+    /// `unix://` URL that clients can use as their base URL.
+    #[cfg(unix)]
+    pub async fn start_unix(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let _ = std::fs::remove_file(path);
+        let listener = tokio::net::UnixListener::bind(path).map_err(|e| AnthropicError::Connection {
+            message: format!("Failed to bind mock server to {}: {}", path.display(), e),
+        })?;
+
+        let state = Arc::new(Mutex::new(State::default()));
+        let accept_state = state.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_connection(stream, accept_state.clone()));
+            }
+        });
+
+        Ok(Self { url: format!("unix://{}", path.display()), state, handle })
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:54321` or `unix:///tmp/mock.sock`.
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Client configuration pointing at this server with retries disabled.
+    pub fn config(&self) -> ClientConfig {
+        ClientConfig::new(MOCK_API_KEY)
+            .with_base_url(self.url())
+            .with_max_retries(0)
+    }
+
+    /// Create a client pointing at this server.
+    pub fn client(&self) -> Result<Anthropic> {
+        Anthropic::with_config(self.config())
+    }
+
+    /// Script the next response for `method` and `path`.
+    ///
+    /// `*` in the path matches a single segment, e.g. `/v1/files/*`.
+    pub fn enqueue(&self, method: &str, path: &str, response: MockResponse) -> &Self {
+        self.lock().scripted.push(Scripted {
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            response,
+        });
+        self
+    }
+
+    /// Script the next `POST /v1/messages` response.
+    pub fn enqueue_message(&self, response: MockResponse) -> &Self {
+        self.enqueue("POST", "/v1/messages", response)
+    }
+
+    /// Number of scripted responses that have not been consumed yet.
+    pub fn pending_responses(&self) -> usize {
+        self.lock().scripted.len()
+    }
+
+    /// All requests received so far, in arrival order.
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Requests received for `method` and `path`.
+    pub fn requests_to(&self, method: &str, path: &str) -> Vec<CapturedRequest> {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|request| request.matches(method, path))
+            .cloned()
+            .collect()
+    }
+
+    /// The most recent request, if any.
+    pub fn last_request(&self) -> Option<CapturedRequest> {
+        self.lock().requests.last().cloned()
+    }
+
+    /// Clear captured requests, scripted responses and stored batches and files.
+    pub fn reset(&self) {
+        *self.lock() = State::default();
+    }
+
+    /// Store a file so it can be fetched through the Files API.
+    pub fn add_file(&self, file: FileObject, content: impl Into<Vec<u8>>) -> &Self {
+        self.lock().files.push((file, content.into()));
+        self
+    }
+
+    /// Mark a stored batch as completed with the given results.
+    ///
+    /// The results are served as JSONL from the batch's output file.
+    pub fn complete_batch(&self, batch_id: &str, results: Vec<BatchResult>) -> Result<MessageBatch> {
+        let content = results
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .join("\n");
+        let output = fixtures::file_object("results.jsonl", "application/jsonl", content.len() as u64);
+
+        let mut state = self.lock();
+        let batch = state
+            .batches
+            .iter_mut()
+            .find(|batch| batch.id == batch_id)
+            .ok_or_else(|| AnthropicError::Other(format!("Unknown mock batch: {}", batch_id)))?;
+        batch.processing_status = BatchStatus::Completed;
+        batch.request_counts.completed = results.len() as u32;
+        batch.ended_at = Some(chrono::Utc::now());
+        batch.output_file_id = Some(output.id.clone());
+        let batch = batch.clone();
+
+        state.files.push((output, content.into_bytes()));
+        Ok(batch)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Serve a single request on an accepted connection.
+async fn serve_connection<S>(stream: S, state: Arc<Mutex<State>>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut stream = BufReader::new(stream);
+    let raw = match server::read_request(&mut stream).await {
+        Ok(Some(raw)) => raw,
+        _ => return,
+    };
+    let (response, request_id) = handle_request(&state, raw);
+    let _ = server::write_response(&mut stream, &response, &request_id).await;
+}
+
+/// Record the request and pick a scripted or default response for it.
+fn handle_request(state: &Mutex<State>, raw: RawRequest) -> (MockResponse, String) {
+    let request = CapturedRequest::from(raw);
+    let mut state = state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    state.request_count += 1;
+    let request_id = format!("req_mock_{}", state.request_count);
+    state.requests.push(request.clone());
+
+    let scripted = state
+        .scripted
+        .iter()
+        .position(|scripted| request.matches(&scripted.method, &scripted.path));
+    let response = match scripted {
+        Some(index) => state.scripted.remove(index).response,
+        None => default_response(&mut state, &request),
+    };
+
+    (response, request_id)
+}
+
+/// Built-in behaviour for requests without a scripted response.
+fn default_response(state: &mut State, request: &CapturedRequest) -> MockResponse {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["v1", "messages"]) => {
+            let body = request.json();
+            let mut message = fixtures::text_message("Hello from the mock server!");
+            if let Some(model) = body["model"].as_str() {
+                message.model = model.to_string();
+            }
+            if body["stream"].as_bool().unwrap_or(false) {
+                MockResponse::stream_message(message)
+            } else {
+                MockResponse::message(message)
+            }
+        }
+
+        ("POST", ["v1", "messages", "count_tokens"]) => {
+            // Rough estimate: one token per four bytes of request content
+            let input_tokens = (request.body.len() / 4).max(1);
+            MockResponse::json(json!({ "input_tokens": input_tokens }))
+        }
+
+        ("POST", ["v1", "messages", "batches"]) => {
+            let total = request.json()["requests"].as_array().map(|r| r.len()).unwrap_or(0);
+            let mut batch = fixtures::batch(BatchStatus::InProgress, total as u32);
+            if let Ok(metadata) = serde_json::from_value::<HashMap<String, String>>(request.json()["metadata"].clone()) {
+                batch.metadata = metadata;
+            }
+            state.batches.push(batch.clone());
+            MockResponse::json(batch)
+        }
+        ("GET", ["v1", "messages", "batches"]) => MockResponse::json(json!({
+            "data": state.batches,
+            "has_more": false,
+            "first_id": state.batches.first().map(|b| b.id.clone()),
+            "last_id": state.batches.last().map(|b| b.id.clone()),
+        })),
+        ("GET", ["v1", "messages", "batches", id]) => match state.batches.iter().find(|b| b.id == *id) {
+            Some(batch) => MockResponse::json(batch),
+            None => not_found("batch", id),
+        },
+        ("POST", ["v1", "messages", "batches", id, "cancel"]) => {
+            match state.batches.iter_mut().find(|b| b.id == *id) {
+                Some(batch) => {
+                    batch.processing_status = BatchStatus::Cancelling;
+                    MockResponse::json(batch.clone())
+                }
+                None => not_found("batch", id),
+            }
+        }
+
+        ("POST", ["v1", "files"]) => {
+            let content_type = request.header("content-type").unwrap_or_default();
+            match server::multipart_part(content_type, &request.body, "file") {
+                Some(part) => {
+                    let mut file = fixtures::file_object(
+                        part.filename.unwrap_or_else(|| "upload".to_string()),
+                        part.content_type.unwrap_or_else(|| "application/octet-stream".to_string()),
+                        part.data.len() as u64,
+                    );
+                    if let Some(purpose) = server::multipart_part(content_type, &request.body, "purpose")
+                        .and_then(|purpose| serde_json::from_slice(&purpose.data).ok())
+                    {
+                        file.purpose = purpose;
+                    }
+                    state.files.push((file.clone(), part.data));
+                    MockResponse::json(file)
+                }
+                None => MockResponse::error(400, "invalid_request_error", "Missing file part"),
+            }
+        }
+        ("GET", ["v1", "files"]) => {
+            let data: Vec<FileObject> = state.files.iter().map(|(file, _)| file.clone()).collect();
+            MockResponse::json(FileList {
+                has_more: false,
+                first_id: data.first().map(|f| f.id.clone()),
+                last_id: data.last().map(|f| f.id.clone()),
+                data,
+            })
+        }
+        ("GET", ["v1", "files", id]) => match state.files.iter().find(|(f, _)| f.id == *id) {
+            Some((file, _)) => MockResponse::json(file),
+            None => not_found("file", id),
+        },
+        ("GET", ["v1", "files", id, "content"]) => match state.files.iter().find(|(f, _)| f.id == *id) {
+            Some((file, content)) => MockResponse::bytes(file.content_type.clone(), content.clone())
+                .with_header("content-disposition", format!("attachment; filename=\"{}\"", file.filename)),
+            None => not_found("file", id),
+        },
+        ("DELETE", ["v1", "files", id]) => match state.files.iter().position(|(f, _)| f.id == *id) {
+            Some(index) => {
+                let (mut file, _) = state.files.remove(index);
+                file.status = crate::types::FileStatus::Deleted;
+                MockResponse::json(file)
+            }
+            None => not_found("file", id),
+        },
+
+        ("GET", ["v1", "models"]) => {
+            let data = fixtures::default_models();
+            MockResponse::json(ModelList {
+                first_id: data.first().map(|m| m.id.clone()),
+                last_id: data.last().map(|m| m.id.clone()),
+                has_more: false,
+                data,
+            })
+        }
+        ("GET", ["v1", "models", id]) => match fixtures::default_models().into_iter().find(|m| m.id == *id) {
+            Some(model) => MockResponse::json(model),
+            None => not_found("model", id),
+        },
+
+        _ => MockResponse::error(404, "not_found_error", &format!("No mock route for {} {}", request.method, request.path)),
+    }
+}
+
+fn not_found(kind: &str, id: &str) -> MockResponse {
+    MockResponse::error(404, "not_found_error", &format!("No {} with id {}", kind, id))
+}
+
+/// Match a path against a pattern where `*` stands for one segment.
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    pattern.len() == path.len()
+        && pattern.iter().zip(&path).all(|(expected, actual)| *expected == "*" || expected == actual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ContentBlock, MessageCreateBuilder};
+    use std::time::Duration;
+
+    fn params() -> crate::types::MessageCreateParams {
+        MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100)
+            .user("Hello")
+            .build()
+    }
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("/v1/files/*", "/v1/files/file_123"));
+        assert!(path_matches("/v1/messages", "/v1/messages"));
+        assert!(!path_matches("/v1/files/*", "/v1/files/file_123/content"));
+        assert!(!path_matches("/v1/messages", "/v1/messages/batches"));
+    }
+
+    #[tokio::test]
+    async fn test_scripted_message_and_capture() {
+        let server = MockServer::start().await.unwrap();
+        server.enqueue_message(MockResponse::text("Scripted reply"));
+
+        let client = server.client().unwrap();
+        let message = client.messages().create(params()).await.unwrap();
+
+        match &message.content[0] {
+            ContentBlock::Text { text, .. } => assert_eq!(text, "Scripted reply"),
+            other => panic!("unexpected block: {:?}", other),
+        }
+        assert_eq!(message.request_id.as_ref().map(|id| id.as_str()), Some("req_mock_1"));
+        assert_eq!(server.pending_responses(), 0);
+
+        let request = server.last_request().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/v1/messages");
+        assert_eq!(request.header("x-api-key"), Some(MOCK_API_KEY));
+        assert_eq!(request.json()["messages"][0]["content"], "Hello");
+    }
+
+    #[tokio::test]
+    async fn test_default_message_echoes_model() {
+        let server = MockServer::start().await.unwrap();
+        let client = server.client().unwrap();
+
+        let message = client.messages().create(params()).await.unwrap();
+        assert_eq!(message.model, "claude-3-5-sonnet-latest");
+    }
+
+    #[tokio::test]
+    async fn test_injected_errors() {
+        let server = MockServer::start().await.unwrap();
+        server
+            .enqueue_message(MockResponse::rate_limited())
+            .enqueue_message(MockResponse::overloaded());
+
+        let client = server.client().unwrap();
+        let error = client.messages().create(params()).await.unwrap_err();
+        assert!(matches!(error, AnthropicError::RateLimit { .. }), "{:?}", error);
+
+        let error = client.messages().create(params()).await.unwrap_err();
+        assert_eq!(error.status_code(), Some(529));
+    }
+
+    #[tokio::test]
+    async fn test_delayed_response_times_out() {
+        let server = MockServer::start().await.unwrap();
+        server.enqueue_message(MockResponse::text("late").with_delay(Duration::from_secs(5)));
+
+        let client = Anthropic::with_config(server.config().with_timeout(Duration::from_millis(200))).unwrap();
+        assert!(client.messages().create(params()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_route_is_not_found() {
+        let server = MockServer::start().await.unwrap();
+        let client = server.client().unwrap();
+
+        let error = client.models().get("claude-unknown").await.unwrap_err();
+        assert!(matches!(error, AnthropicError::NotFound { .. }), "{:?}", error);
+    }
+}
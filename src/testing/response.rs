@@ -0,0 +1,292 @@
+//! Scripted responses for the mock server.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::types::{ContentBlock, Message};
+
+use super::fixtures;
+use super::server::DEFAULT_EVENT_DELAY;
+
+/// Body of a scripted response.
+#[derive(Debug, Clone)]
+pub(crate) enum MockBody {
+    Json(Value),
+    Bytes { content_type: String, data: Vec<u8> },
+    Sse {
+        events: Vec<(String, String)>,
+        disconnect_after: Option<usize>,
+        event_delay: Option<Duration>,
+    },
+}
+
+/// A response the mock server will return for a single request.
+///
+/// Responses are built with the constructors below and tweaked with the
+/// `with_*` modifiers, e.g. to delay the reply past the client timeout or to
+/// drop the connection in the middle of a stream.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub(crate) status: u16,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: MockBody,
+    pub(crate) delay: Option<Duration>,
+}
+
+impl MockResponse {
+    /// A `200 OK` response with a JSON body.
+    pub fn json(body: impl Serialize) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body: MockBody::Json(serde_json::to_value(body).expect("mock response body must serialize")),
+            delay: None,
+        }
+    }
+
+    /// A `200 OK` response with a raw body and content type.
+    pub fn bytes(content_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body: MockBody::Bytes { content_type: content_type.into(), data: data.into() },
+            delay: None,
+        }
+    }
+
+    /// A non-streaming `/v1/messages` response.
+    pub fn message(message: Message) -> Self {
+        Self::json(message)
+    }
+
+    /// A non-streaming `/v1/messages` response containing a single text block.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::message(fixtures::text_message(text))
+    }
+
+    /// A Server-Sent Events response built from raw `(event, data)` pairs.
+    pub fn sse<E, D>(events: impl IntoIterator<Item = (E, D)>) -> Self
+    where
+        E: Into<String>,
+        D: Serialize,
+    {
+        let events = events
+            .into_iter()
+            .map(|(event, data)| {
+                let data = serde_json::to_string(&data).expect("mock event data must serialize");
+                (event.into(), data)
+            })
+            .collect();
+
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body: MockBody::Sse { events, disconnect_after: None, event_delay: Some(DEFAULT_EVENT_DELAY) },
+            delay: None,
+        }
+    }
+
+    /// A streaming response that replays `message` as the API would emit it.
+    ///
+    /// Text blocks are split into word-sized `text_delta` events and tool inputs
+    /// (including server tool calls) are sent as a single `input_json_delta`.
+    pub fn stream_message(message: Message) -> Self {
+        Self::sse(message_events(&message))
+    }
+
+    /// A streaming response containing a single text block.
+    pub fn text_stream(text: impl Into<String>) -> Self {
+        Self::stream_message(fixtures::text_message(text))
+    }
+
+    /// An API error response in the standard `{"type": "error", "error": {...}}` shape.
+    pub fn error(status: u16, error_type: &str, message: &str) -> Self {
+        let mut response = Self::json(json!({
+            "type": "error",
+            "error": { "type": error_type, "message": message }
+        }));
+        response.status = status;
+        response
+    }
+
+    /// A `429 Too Many Requests` rate limit error.
+    pub fn rate_limited() -> Self {
+        Self::error(429, "rate_limit_error", "Number of requests has exceeded your rate limit")
+            .with_header("retry-after", "1")
+    }
+
+    /// A `529` overloaded error.
+    pub fn overloaded() -> Self {
+        Self::error(529, "overloaded_error", "Overloaded")
+    }
+
+    /// Override the status code.
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Add a response header.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into().to_lowercase(), value.into()));
+        self
+    }
+
+    /// Wait before sending anything, e.g. to trigger a client timeout.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Drop the connection after `events` SSE events have been sent.
+    ///
+    /// Has no effect on non-streaming responses.
+    pub fn disconnect_after(mut self, events: usize) -> Self {
+        if let MockBody::Sse { disconnect_after, .. } = &mut self.body {
+            *disconnect_after = Some(events);
+        }
+        self
+    }
+
+    /// Pause between SSE events.
+    ///
+    /// Has no effect on non-streaming responses.
+    pub fn with_event_delay(mut self, delay: Duration) -> Self {
+        if let MockBody::Sse { event_delay, .. } = &mut self.body {
+            *event_delay = Some(delay);
+        }
+        self
+    }
+
+    /// The status code that will be returned.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+}
+
+/// Convert a complete message into the `(event, data)` SSE sequence the API would emit.
+pub fn message_events(message: &Message) -> Vec<(String, Value)> {
+    let mut events = Vec::new();
+
+    let mut start = message.clone();
+    start.content = Vec::new();
+    start.stop_reason = None;
+    start.stop_sequence = None;
+    start.usage.output_tokens = 0;
+    events.push(("message_start".to_string(), json!({ "type": "message_start", "message": start })));
+
+    for (index, block) in message.content.iter().enumerate() {
+        let (start_block, deltas) = block_events(block);
+        events.push((
+            "content_block_start".to_string(),
+            json!({ "type": "content_block_start", "index": index, "content_block": start_block }),
+        ));
+        for delta in deltas {
+            events.push((
+                "content_block_delta".to_string(),
+                json!({ "type": "content_block_delta", "index": index, "delta": delta }),
+            ));
+        }
+        events.push((
+            "content_block_stop".to_string(),
+            json!({ "type": "content_block_stop", "index": index }),
+        ));
+    }
+
+    events.push((
+        "message_delta".to_string(),
+        json!({
+            "type": "message_delta",
+            "delta": { "stop_reason": message.stop_reason, "stop_sequence": message.stop_sequence },
+            "usage": { "output_tokens": message.usage.output_tokens }
+        }),
+    ));
+    events.push(("message_stop".to_string(), json!({ "type": "message_stop" })));
+
+    events
+}
+
+/// Split a content block into its `content_block_start` payload and deltas.
+fn block_events(block: &ContentBlock) -> (Value, Vec<Value>) {
+    match block {
+        ContentBlock::Text { text, citations } => {
+            let mut deltas: Vec<Value> = citations
+                .iter()
+                .flatten()
+                .map(|citation| json!({ "type": "citations_delta", "citation": citation }))
+                .collect();
+            deltas.extend(
+                text.split_inclusive(' ')
+                    .map(|chunk| json!({ "type": "text_delta", "text": chunk })),
+            );
+            let start = match citations {
+                Some(_) => json!({ "type": "text", "text": "", "citations": [] }),
+                None => json!({ "type": "text", "text": "" }),
+            };
+            (start, deltas)
+        }
+        ContentBlock::ToolUse { id, name, input } => {
+            let partial_json = serde_json::to_string(input).unwrap_or_default();
+            (
+                json!({ "type": "tool_use", "id": id, "name": name, "input": {} }),
+                vec![json!({ "type": "input_json_delta", "partial_json": partial_json })],
+            )
+        }
+        ContentBlock::ServerToolUse { id, name, input } => {
+            let partial_json = serde_json::to_string(input).unwrap_or_default();
+            (
+                json!({ "type": "server_tool_use", "id": id, "name": name, "input": {} }),
+                vec![json!({ "type": "input_json_delta", "partial_json": partial_json })],
+            )
+        }
+        ContentBlock::Thinking { thinking, signature } => {
+            let mut deltas: Vec<Value> = thinking
+                .split_inclusive(' ')
+                .map(|chunk| json!({ "type": "thinking_delta", "thinking": chunk }))
+                .collect();
+            deltas.push(json!({ "type": "signature_delta", "signature": signature }));
+            (json!({ "type": "thinking", "thinking": "", "signature": "" }), deltas)
+        }
+        other => (serde_json::to_value(other).unwrap_or(Value::Null), Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_response_shape() {
+        let response = MockResponse::overloaded();
+        assert_eq!(response.status(), 529);
+        match response.body {
+            MockBody::Json(value) => {
+                assert_eq!(value["type"], "error");
+                assert_eq!(value["error"]["type"], "overloaded_error");
+            }
+            _ => panic!("expected JSON body"),
+        }
+    }
+
+    #[test]
+    fn test_message_events_sequence() {
+        let events = message_events(&fixtures::text_message("Hello there"));
+        let names: Vec<&str> = events.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "message_start",
+                "content_block_start",
+                "content_block_delta",
+                "content_block_delta",
+                "content_block_stop",
+                "message_delta",
+                "message_stop",
+            ]
+        );
+        assert_eq!(events[2].1["delta"]["text"], "Hello ");
+        assert_eq!(events[5].1["delta"]["stop_reason"], "end_turn");
+    }
+}
@@ -0,0 +1,264 @@
+//! Minimal HTTP/1.1 plumbing for the mock server.
+//!
+//! Only the subset of HTTP needed by the SDK is implemented, over TCP or Unix
+//! sockets: one request per connection, `content-length` or chunked request
+//! bodies, and chunked responses for Server-Sent Events so that disconnects
+//! can be simulated.
+
+use std::io;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use super::response::{MockBody, MockResponse};
+
+/// A request as read off the wire.
+#[derive(Debug, Clone)]
+pub(crate) struct RawRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl RawRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Read a single HTTP request from the stream.
+///
+/// Returns `Ok(None)` if the peer closed the connection before sending anything.
+pub(crate) async fn read_request<S>(stream: &mut BufReader<S>) -> io::Result<Option<RawRequest>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut request_line = String::new();
+    if stream.read_line(&mut request_line).await? == 0 {
+        return Ok(None);
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or("/").to_string();
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target, None),
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    let mut request = RawRequest { method, path, query, headers, body: Vec::new() };
+
+    if request.header("transfer-encoding").map(|v| v.eq_ignore_ascii_case("chunked")).unwrap_or(false) {
+        request.body = read_chunked_body(stream).await?;
+    } else if let Some(length) = request.header("content-length").and_then(|v| v.parse::<usize>().ok()) {
+        let mut body = vec![0u8; length];
+        stream.read_exact(&mut body).await?;
+        request.body = body;
+    }
+
+    Ok(Some(request))
+}
+
+async fn read_chunked_body<S>(stream: &mut BufReader<S>) -> io::Result<Vec<u8>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut body = Vec::new();
+    loop {
+        let mut size_line = String::new();
+        stream.read_line(&mut size_line).await?;
+        let size_str = size_line.trim().split(';').next().unwrap_or("0");
+        let size = usize::from_str_radix(size_str, 16)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        if size == 0 {
+            // Consume optional trailers up to the terminating blank line
+            loop {
+                let mut trailer = String::new();
+                if stream.read_line(&mut trailer).await? == 0 || trailer.trim().is_empty() {
+                    break;
+                }
+            }
+            return Ok(body);
+        }
+
+        let mut chunk = vec![0u8; size];
+        stream.read_exact(&mut chunk).await?;
+        body.extend_from_slice(&chunk);
+
+        let mut crlf = [0u8; 2];
+        stream.read_exact(&mut crlf).await?;
+    }
+}
+
+/// Write a mock response to the stream, honouring delays and simulated disconnects.
+pub(crate) async fn write_response<S>(
+    stream: &mut BufReader<S>,
+    response: &MockResponse,
+    request_id: &str,
+) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    if let Some(delay) = response.delay {
+        tokio::time::sleep(delay).await;
+    }
+
+    let stream = stream.get_mut();
+    let mut head = format!("HTTP/1.1 {} {}\r\n", response.status, reason_phrase(response.status));
+    head.push_str(&format!("request-id: {}\r\n", request_id));
+    head.push_str("connection: close\r\n");
+    for (key, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", key, value));
+    }
+
+    match &response.body {
+        MockBody::Json(value) => {
+            let body = serde_json::to_vec(value)?;
+            head.push_str("content-type: application/json\r\n");
+            head.push_str(&format!("content-length: {}\r\n\r\n", body.len()));
+            stream.write_all(head.as_bytes()).await?;
+            stream.write_all(&body).await?;
+        }
+        MockBody::Bytes { content_type, data } => {
+            head.push_str(&format!("content-type: {}\r\n", content_type));
+            head.push_str(&format!("content-length: {}\r\n\r\n", data.len()));
+            stream.write_all(head.as_bytes()).await?;
+            stream.write_all(data).await?;
+        }
+        MockBody::Sse { events, disconnect_after, event_delay } => {
+            head.push_str("content-type: text/event-stream\r\n");
+            head.push_str("cache-control: no-cache\r\n");
+            head.push_str("transfer-encoding: chunked\r\n\r\n");
+            stream.write_all(head.as_bytes()).await?;
+            stream.flush().await?;
+
+            for (index, (event, data)) in events.iter().enumerate() {
+                if disconnect_after.map(|limit| index >= limit).unwrap_or(false) {
+                    // Drop the connection without the terminating chunk
+                    stream.shutdown().await?;
+                    return Ok(());
+                }
+                let frame = format!("event: {}\ndata: {}\n\n", event, data);
+                write_chunk(stream, frame.as_bytes()).await?;
+                stream.flush().await?;
+                if let Some(delay) = event_delay {
+                    tokio::time::sleep(*delay).await;
+                }
+            }
+            stream.write_all(b"0\r\n\r\n").await?;
+        }
+    }
+
+    stream.flush().await?;
+    stream.shutdown().await
+}
+
+async fn write_chunk<W>(stream: &mut W, data: &[u8]) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    stream.write_all(format!("{:x}\r\n", data.len()).as_bytes()).await?;
+    stream.write_all(data).await?;
+    stream.write_all(b"\r\n").await
+}
+
+/// Extract a single part from a `multipart/form-data` body.
+///
+/// Only the part headers are decoded as text; `data` is sliced from the raw
+/// body so binary uploads survive intact.
+pub(crate) fn multipart_part(content_type: &str, body: &[u8], field: &str) -> Option<MultipartPart> {
+    let boundary = content_type
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))?
+        .trim_matches('"');
+    let delimiter = format!("--{}", boundary);
+
+    split_bytes(body, delimiter.as_bytes()).into_iter().find_map(|section| {
+        let section = section.strip_prefix(b"\r\n")?;
+        let head_end = find_bytes(section, b"\r\n\r\n")?;
+        let head = std::str::from_utf8(&section[..head_end]).ok()?;
+        let content = &section[head_end + 4..];
+        let disposition = head
+            .lines()
+            .find(|line| line.to_lowercase().starts_with("content-disposition"))?;
+        if !disposition.contains(&format!("name=\"{}\"", field)) {
+            return None;
+        }
+        let filename = disposition
+            .split(';')
+            .find_map(|param| param.trim().strip_prefix("filename="))
+            .map(|name| name.trim_matches('"').to_string());
+        let content_type = head.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case("content-type").then(|| value.trim().to_string())
+        });
+        Some(MultipartPart {
+            filename,
+            content_type,
+            data: content.strip_suffix(b"\r\n").unwrap_or(content).to_vec(),
+        })
+    })
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn split_bytes<'a>(mut haystack: &'a [u8], needle: &[u8]) -> Vec<&'a [u8]> {
+    let mut sections = Vec::new();
+    while let Some(index) = find_bytes(haystack, needle) {
+        sections.push(&haystack[..index]);
+        haystack = &haystack[index + needle.len()..];
+    }
+    sections.push(haystack);
+    sections
+}
+
+/// A decoded `multipart/form-data` part.
+pub(crate) struct MultipartPart {
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        529 => "Overloaded",
+        _ => "Unknown",
+    }
+}
+
+/// Default delay between SSE frames so clients observe incremental delivery.
+pub(crate) const DEFAULT_EVENT_DELAY: Duration = Duration::from_millis(1);
@@ -3,9 +3,9 @@
 //! This module provides abstractions for managing multi-turn conversations
 //! that involve tool use, handling the back-and-forth between Claude and tools.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use crate::client::Anthropic;
-use crate::types::{Message, ToolChoice, ToolResult, MessageCreateBuilder};
+use crate::types::{Message, MessageParam, Role, ThinkingConfig, ToolChoice, ToolResult, MessageCreateBuilder};
 use super::{ToolRegistry, ToolExecutor, ToolExecutionConfig, ToolOperationResult, ToolError};
 
 /// High-level tool conversation manager.
@@ -24,6 +24,9 @@ pub struct ToolConversation {
     
     /// Configuration for the conversation.
     config: ConversationConfig,
+    
+    /// Messages exchanged so far, sent in full on every turn.
+    history: Mutex<Vec<MessageParam>>,
 }
 
 /// Configuration for tool conversations.
@@ -46,6 +49,12 @@ pub struct ConversationConfig {
     
     /// Tool execution configuration.
     pub execution_config: ToolExecutionConfig,
+    
+    /// Extended thinking configuration.
+    pub thinking: Option<ThinkingConfig>,
+    
+    /// Maximum number of times a paused turn is resubmitted; `None` disables continuation.
+    pub max_continuations: Option<u32>,
 }
 
 impl Default for ConversationConfig {
@@ -54,9 +63,11 @@ impl Default for ConversationConfig {
             max_turns: 10,
             model: "claude-3-5-sonnet-latest".to_string(),
             max_tokens: 1024,
-            tool_choice: Some(ToolChoice::Auto),
+            tool_choice: Some(ToolChoice::auto()),
             auto_execute_tools: true,
             execution_config: ToolExecutionConfig::default(),
+            thinking: None,
+            max_continuations: None,
         }
     }
 }
@@ -64,13 +75,7 @@ impl Default for ConversationConfig {
 impl ToolConversation {
     /// Create a new tool conversation.
     pub fn new(client: Arc<Anthropic>, registry: Arc<ToolRegistry>) -> Self {
-        let executor = ToolExecutor::new(registry.clone());
-        Self {
-            client,
-            registry: registry.clone(),
-            executor,
-            config: ConversationConfig::default(),
-        }
+        Self::with_config(client, registry, ConversationConfig::default())
     }
 
     /// Create a new tool conversation with custom configuration.
@@ -79,12 +84,14 @@ impl ToolConversation {
         registry: Arc<ToolRegistry>,
         config: ConversationConfig,
     ) -> Self {
-        let executor = ToolExecutor::with_config(registry.clone(), config.execution_config.clone());
+        let mut executor = ToolExecutor::with_config(registry.clone(), config.execution_config.clone());
+        executor.set_tool_choice(config.tool_choice.clone());
         Self {
             client,
             registry: registry.clone(),
             executor,
             config,
+            history: Mutex::new(Vec::new()),
         }
     }
 
@@ -92,33 +99,22 @@ impl ToolConversation {
     ///
     /// This method initiates a conversation and returns the first response from Claude.
     /// If Claude uses tools, they will be automatically executed if `auto_execute_tools` is enabled.
+    /// Any previous history is discarded.
     pub async fn start(&self, user_message: impl Into<String>) -> ToolOperationResult<Message> {
-        let tools = self.registry.get_tool_definitions();
-        
-        let mut builder = MessageCreateBuilder::new(&self.config.model, self.config.max_tokens)
-            .user(user_message.into());
-
-        // Add tools if available
-        if !tools.is_empty() {
-            builder = builder.tools(tools);
-            
-            if let Some(ref tool_choice) = self.config.tool_choice {
-                builder = builder.tool_choice(tool_choice.clone());
-            }
-        }
-
-        let message = self.client.messages()
-            .create(builder.build())
-            .await
-            .map_err(|e| ToolError::ExecutionFailed { source: e.into() })?;
+        let user_message = MessageParam {
+            role: Role::User,
+            content: user_message.into().into(),
+        };
+        *self.history.lock().unwrap() = vec![user_message];
 
-        Ok(message)
+        self.send_history().await
     }
 
     /// Continue a conversation by processing tool uses and getting the next response.
     ///
     /// This method takes a message that may contain tool use requests, executes the tools,
-    /// and returns Claude's response incorporating the tool results.
+    /// and returns Claude's response incorporating the tool results. The assistant message
+    /// is replayed unmodified, so thinking blocks are preserved across tool use turns.
     pub async fn continue_with_tools(&self, message: &Message) -> ToolOperationResult<Option<Message>> {
         let tool_uses = self.executor.extract_tool_uses(message);
         
@@ -148,38 +144,31 @@ impl ToolConversation {
             }
         }
 
-        // Create a follow-up message with tool results
+        // Create a follow-up message with tool results, keeping images and documents intact
         use crate::types::messages::{MessageContent, ContentBlockParam};
         
-        // Convert tool results to content blocks
-        let tool_result_blocks: Vec<ContentBlockParam> = results.into_iter().map(|result| {
-            // Convert ToolResultContent to String for ContentBlockParam::ToolResult
-            let content_string = match result.content {
-                crate::types::ToolResultContent::Text(text) => Some(text),
-                crate::types::ToolResultContent::Json(json) => Some(json.to_string()),
-                crate::types::ToolResultContent::Blocks(blocks) => {
-                    // Convert blocks to a simple text representation
-                    let text_parts: Vec<String> = blocks.into_iter().map(|block| {
-                        match block {
-                            crate::types::ToolResultBlock::Text { text } => text,
-                            crate::types::ToolResultBlock::Image { .. } => "[Image]".to_string(),
-                        }
-                    }).collect();
-                    Some(text_parts.join("\n"))
-                }
-            };
-            
-            ContentBlockParam::ToolResult {
-                tool_use_id: result.tool_use_id,
-                content: content_string,
-                is_error: result.is_error,
-            }
-        }).collect();
-        
-        let mut builder = MessageCreateBuilder::new(&self.config.model, self.config.max_tokens)
-            .user(MessageContent::Blocks(tool_result_blocks));
-            
-        // Add tools again for potential follow-up tool use
+        let tool_result_blocks: Vec<ContentBlockParam> = results.into_iter().map(ContentBlockParam::from).collect();
+
+        {
+            let mut history = self.history.lock().unwrap();
+            history.push(message.clone().into_param());
+            history.push(MessageParam {
+                role: Role::User,
+                content: MessageContent::Blocks(tool_result_blocks),
+            });
+        }
+
+        self.send_history().await.map(Some)
+    }
+
+    /// Send the full history with the configured tools and thinking settings.
+    async fn send_history(&self) -> ToolOperationResult<Message> {
+        let mut builder = MessageCreateBuilder::new(&self.config.model, self.config.max_tokens);
+        for message in self.history.lock().unwrap().iter() {
+            builder = builder.message(message.role.clone(), message.content.clone());
+        }
+
+        // Add tools if available
         let tools = self.registry.get_tool_definitions();
         if !tools.is_empty() {
             builder = builder.tools(tools);
@@ -188,13 +177,19 @@ impl ToolConversation {
                 builder = builder.tool_choice(tool_choice.clone());
             }
         }
-        
-        let next_message = self.client.messages()
+
+        if let Some(ref thinking) = self.config.thinking {
+            builder = builder.thinking(thinking.clone());
+        }
+
+        let mut messages = self.client.messages();
+        if let Some(max_continuations) = self.config.max_continuations {
+            messages = messages.auto_continue(max_continuations);
+        }
+        messages
             .create(builder.build())
             .await
-            .map_err(|e| ToolError::ExecutionFailed { source: e.into() })?;
-
-        Ok(Some(next_message))
+            .map_err(|e| ToolError::ExecutionFailed { source: e.into() })
     }
 
     /// Execute a complete conversation until completion or max turns reached.
@@ -237,6 +232,13 @@ impl ToolConversation {
         &self.executor
     }
 
+    /// Get the messages sent so far, in order.
+    ///
+    /// The latest response is not included until tool results are sent for it.
+    pub fn history(&self) -> Vec<MessageParam> {
+        self.history.lock().unwrap().clone()
+    }
+
     /// Get the conversation configuration.
     pub fn config(&self) -> &ConversationConfig {
         &self.config
@@ -246,6 +248,7 @@ impl ToolConversation {
     pub fn set_config(&mut self, config: ConversationConfig) {
         self.config = config;
         self.executor.set_config(self.config.execution_config.clone());
+        self.executor.set_tool_choice(self.config.tool_choice.clone());
     }
 
 
@@ -300,6 +303,18 @@ impl ConversationConfigBuilder {
         self
     }
 
+    /// Enable extended thinking with the given configuration.
+    pub fn thinking(mut self, thinking: ThinkingConfig) -> Self {
+        self.config.thinking = Some(thinking);
+        self
+    }
+
+    /// Automatically resubmit turns paused by server tools, at most `max_continuations` times.
+    pub fn auto_continue(mut self, max_continuations: u32) -> Self {
+        self.config.max_continuations = Some(max_continuations);
+        self
+    }
+
     /// Build the configuration.
     pub fn build(self) -> ConversationConfig {
         self.config
@@ -315,6 +330,20 @@ impl Default for ConversationConfigBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{fixtures, MockResponse, MockServer};
+    use crate::tools::ToolFunction;
+    use crate::types::{ContentBlock, StopReason, Tool};
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    struct WeatherTool;
+
+    #[async_trait]
+    impl ToolFunction for WeatherTool {
+        async fn execute(&self, _input: serde_json::Value) -> Result<ToolResult, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(ToolResult::success("", "Sunny, 22C"))
+        }
+    }
 
     #[test]
     fn test_conversation_config_builder() {
@@ -322,14 +351,14 @@ mod tests {
             .max_turns(5)
             .model("claude-3-5-sonnet-latest")
             .max_tokens(2048)
-            .tool_choice(ToolChoice::Any)
+            .tool_choice(ToolChoice::any())
             .auto_execute_tools(false)
             .build();
 
         assert_eq!(config.max_turns, 5);
         assert_eq!(config.model, "claude-3-5-sonnet-latest");
         assert_eq!(config.max_tokens, 2048);
-        assert_eq!(config.tool_choice, Some(ToolChoice::Any));
+        assert_eq!(config.tool_choice, Some(ToolChoice::any()));
         assert!(!config.auto_execute_tools);
     }
 
@@ -339,7 +368,131 @@ mod tests {
         assert_eq!(config.max_turns, 10);
         assert_eq!(config.model, "claude-3-5-sonnet-latest");
         assert_eq!(config.max_tokens, 1024);
-        assert_eq!(config.tool_choice, Some(ToolChoice::Auto));
+        assert_eq!(config.tool_choice, Some(ToolChoice::auto()));
         assert!(config.auto_execute_tools);
     }
-} 
\ No newline at end of file
+
+    struct ChartTool;
+
+    #[async_trait]
+    impl ToolFunction for ChartTool {
+        async fn execute(&self, _input: serde_json::Value) -> Result<ToolResult, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(ToolResult::with_blocks("", vec![
+                crate::types::ToolResultBlock::text("Rendered chart"),
+                crate::types::ToolResultBlock::image_base64("image/png", "iVBORw0KGgo="),
+            ]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paused_turns_are_resubmitted() {
+        let server = MockServer::start().await.unwrap();
+        let paused = fixtures::message(
+            vec![ContentBlock::ServerToolUse {
+                id: "srvtoolu_1".to_string(),
+                name: "web_search".to_string(),
+                input: json!({"query": "weather"}),
+            }],
+            StopReason::PauseTurn,
+        );
+        server
+            .enqueue_message(MockResponse::message(paused))
+            .enqueue_message(MockResponse::text("Sunny everywhere."));
+
+        let config = ConversationConfigBuilder::new().auto_continue(3).build();
+        let conversation = ToolConversation::with_config(
+            Arc::new(server.client().unwrap()),
+            Arc::new(ToolRegistry::new()),
+            config,
+        );
+
+        let reply = conversation.execute_until_complete("Search the weather").await.unwrap();
+        assert_eq!(reply.stop_reason, Some(StopReason::EndTurn));
+        assert_eq!(reply.content.len(), 2);
+
+        let body = server.last_request().unwrap().json();
+        assert_eq!(body["messages"][1]["role"], "assistant");
+        assert_eq!(body["messages"][1]["content"][0]["type"], "server_tool_use");
+    }
+
+    #[tokio::test]
+    async fn test_image_tool_results_are_sent_as_blocks() {
+        let server = MockServer::start().await.unwrap();
+        server
+            .enqueue_message(MockResponse::message(fixtures::tool_use_message("render_chart", json!({}))))
+            .enqueue_message(MockResponse::text("The chart shows growth."));
+
+        let mut registry = ToolRegistry::new();
+        registry
+            .register("render_chart", Tool::new("render_chart", "Render a chart").build(), Box::new(ChartTool))
+            .unwrap();
+        let conversation = ToolConversation::new(Arc::new(server.client().unwrap()), Arc::new(registry));
+
+        conversation.execute_until_complete("Plot revenue").await.unwrap();
+
+        let body = server.last_request().unwrap().json();
+        let tool_result = &body["messages"][2]["content"][0];
+        assert_eq!(tool_result["type"], "tool_result");
+        assert_eq!(tool_result["content"][0], json!({"type": "text", "text": "Rendered chart"}));
+        assert_eq!(tool_result["content"][1]["type"], "image");
+        assert_eq!(tool_result["content"][1]["source"]["data"], "iVBORw0KGgo=");
+    }
+
+    #[tokio::test]
+    async fn test_thinking_blocks_preserved_across_tool_use() {
+        let server = MockServer::start().await.unwrap();
+        let tool_turn = fixtures::message(
+            vec![
+                ContentBlock::Thinking {
+                    thinking: "I should check the weather".to_string(),
+                    signature: "sig_weather".to_string(),
+                },
+                ContentBlock::RedactedThinking { data: "opaque".to_string() },
+                ContentBlock::ToolUse {
+                    id: "toolu_weather".to_string(),
+                    name: "get_weather".to_string(),
+                    input: json!({"location": "Paris"}),
+                },
+            ],
+            StopReason::ToolUse,
+        );
+        server
+            .enqueue_message(MockResponse::message(tool_turn))
+            .enqueue_message(MockResponse::text("It is sunny in Paris."));
+
+        let mut registry = ToolRegistry::new();
+        let definition = Tool::new("get_weather", "Get the weather")
+            .parameter("location", "string", "City name")
+            .required("location")
+            .build();
+        registry.register("get_weather", definition, Box::new(WeatherTool)).unwrap();
+
+        let config = ConversationConfigBuilder::new()
+            .thinking(ThinkingConfig::enabled(1024))
+            .build();
+        let conversation = ToolConversation::with_config(
+            Arc::new(server.client().unwrap()),
+            Arc::new(registry),
+            config,
+        );
+
+        let reply = conversation.execute_until_complete("Weather in Paris?").await.unwrap();
+        assert_eq!(reply.content, vec![ContentBlock::Text { text: "It is sunny in Paris.".to_string(), citations: None }]);
+
+        let body = server.last_request().unwrap().json();
+        assert_eq!(body["thinking"], json!({"type": "enabled", "budget_tokens": 1024}));
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["content"], "Weather in Paris?");
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(messages[1]["content"][0], json!({
+            "type": "thinking",
+            "thinking": "I should check the weather",
+            "signature": "sig_weather",
+        }));
+        assert_eq!(messages[1]["content"][1], json!({"type": "redacted_thinking", "data": "opaque"}));
+        assert_eq!(messages[1]["content"][2]["type"], "tool_use");
+        assert_eq!(messages[2]["content"][0]["tool_use_id"], "toolu_weather");
+        assert_eq!(conversation.history().len(), 3);
+    }
+}
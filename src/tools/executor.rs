@@ -7,7 +7,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
 
-use crate::types::{ToolUse, ToolResult, Message, ContentBlock};
+use crate::types::{ToolUse, ToolResult, ToolChoice, Message};
 use super::{ToolRegistry, ToolError, ToolOperationResult};
 
 /// Configuration for tool execution.
@@ -55,6 +55,9 @@ pub struct ToolExecutor {
     
     /// Configuration for tool execution.
     config: ToolExecutionConfig,
+    
+    /// Tool choice sent with the request; disabling parallel tool use forces sequential execution.
+    tool_choice: Option<ToolChoice>,
 }
 
 impl ToolExecutor {
@@ -63,12 +66,19 @@ impl ToolExecutor {
         Self {
             registry,
             config: ToolExecutionConfig::default(),
+            tool_choice: None,
         }
     }
 
     /// Create a new tool executor with custom configuration.
     pub fn with_config(registry: Arc<ToolRegistry>, config: ToolExecutionConfig) -> Self {
-        Self { registry, config }
+        Self { registry, config, tool_choice: None }
+    }
+
+    /// Set the tool choice the tool uses were requested with.
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
     }
 
     /// Execute a single tool with retry logic.
@@ -124,13 +134,20 @@ impl ToolExecutor {
 
     /// Execute multiple tools, potentially in parallel.
     ///
+    /// Tools run sequentially when parallel execution is disabled in the
+    /// configuration or the tool choice disables parallel tool use.
+    ///
     /// # Arguments
     /// * `tool_uses` - Vector of tool use requests
     ///
     /// # Returns
     /// Vector of tool results in the same order as input.
     pub async fn execute_multiple(&self, tool_uses: &[ToolUse]) -> Vec<ToolOperationResult<ToolResult>> {
-        if self.config.parallel_execution && tool_uses.len() > 1 {
+        let parallel_disabled = self
+            .tool_choice
+            .as_ref()
+            .is_some_and(ToolChoice::is_parallel_tool_use_disabled);
+        if self.config.parallel_execution && !parallel_disabled && tool_uses.len() > 1 {
             self.execute_parallel_with_concurrency(tool_uses).await
         } else {
             let mut results = Vec::with_capacity(tool_uses.len());
@@ -179,21 +196,7 @@ impl ToolExecutor {
     /// # Returns
     /// Vector of tool use requests found in the message.
     pub fn extract_tool_uses(&self, message: &Message) -> Vec<ToolUse> {
-        message
-            .content
-            .iter()
-            .filter_map(|block| {
-                if let ContentBlock::ToolUse { id, name, input } = block {
-                    Some(ToolUse {
-                        id: id.clone(),
-                        name: name.clone(),
-                        input: input.clone(),
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect()
+        message.tool_uses()
     }
 
     /// Check if a tool should be retried based on the error in the result.
@@ -228,6 +231,16 @@ impl ToolExecutor {
     pub fn set_config(&mut self, config: ToolExecutionConfig) {
         self.config = config;
     }
+
+    /// Get the tool choice, if set.
+    pub fn tool_choice(&self) -> Option<&ToolChoice> {
+        self.tool_choice.as_ref()
+    }
+
+    /// Update the tool choice.
+    pub fn set_tool_choice(&mut self, tool_choice: Option<ToolChoice>) {
+        self.tool_choice = tool_choice;
+    }
 }
 
 /// Builder for creating tool execution configurations.
@@ -455,6 +468,37 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_disabled_parallel_tool_use_runs_sequentially() {
+        let mut registry = ToolRegistry::new();
+        registry
+            .register(
+                "slow_tool",
+                Tool::new("slow_tool", "Slow tool for testing parallelism").build(),
+                Box::new(TestSlowTool {
+                    delay: Duration::from_millis(50),
+                }),
+            )
+            .unwrap();
+
+        let executor = ToolExecutor::new(Arc::new(registry))
+            .with_tool_choice(ToolChoice::auto().disable_parallel_tool_use());
+
+        let tool_uses: Vec<ToolUse> = (1..=3)
+            .map(|i| ToolUse {
+                id: format!("test_{}", i),
+                name: "slow_tool".to_string(),
+                input: json!({}),
+            })
+            .collect();
+
+        let start = std::time::Instant::now();
+        let results = executor.execute_multiple(&tool_uses).await;
+
+        assert!(start.elapsed() >= Duration::from_millis(150));
+        assert!(results.iter().all(|result| result.is_ok()));
+    }
+
     #[test]
     fn test_config_builder() {
         let config = ToolExecutionConfigBuilder::new()
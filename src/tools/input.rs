@@ -0,0 +1,135 @@
+//! Typed tool inputs and tools that describe themselves.
+//!
+//! These traits are usually implemented with the `#[derive(ToolInput)]` and
+//! `#[tool]` macros from the `macros` feature, but can be implemented by hand.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::types::{Tool, ToolResult, ToolResultContent};
+use super::ToolFunction;
+
+/// A type Claude can fill in as the input of a tool.
+///
+/// The tool definition, including its JSON schema, is derived from the type,
+/// so the schema and the parsing code can't drift apart.
+///
+/// # Examples
+/// ```rust
+/// use anthropic_sdk::tools::ToolInput;
+/// use anthropic_sdk::types::Tool;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Lookup {
+///     query: String,
+/// }
+///
+/// impl ToolInput for Lookup {
+///     fn tool() -> Tool {
+///         Tool::new("lookup", "Look something up")
+///             .parameter("query", "string", "What to look up")
+///             .required("query")
+///             .build()
+///     }
+/// }
+///
+/// assert_eq!(Lookup::tool().name, "lookup");
+/// ```
+pub trait ToolInput: DeserializeOwned + Send + 'static {
+    /// The tool definition whose input schema describes this type.
+    fn tool() -> Tool;
+}
+
+/// A tool implementation that carries its own definition.
+///
+/// Register it with [`ToolRegistry::register_tool`](super::ToolRegistry::register_tool).
+pub trait DefinedTool: ToolFunction {
+    /// The definition sent to Claude.
+    fn definition(&self) -> Tool;
+}
+
+/// Values a typed tool can return.
+///
+/// The `tool_use_id` is left empty; the registry fills it in when the tool runs.
+pub trait ToolOutput {
+    /// Convert the value into a tool result.
+    fn into_tool_result(self) -> ToolResult;
+}
+
+impl ToolOutput for ToolResult {
+    fn into_tool_result(self) -> ToolResult {
+        self
+    }
+}
+
+impl ToolOutput for ToolResultContent {
+    fn into_tool_result(self) -> ToolResult {
+        ToolResult { tool_use_id: String::new(), content: self, is_error: None }
+    }
+}
+
+impl ToolOutput for String {
+    fn into_tool_result(self) -> ToolResult {
+        ToolResult::success("", self)
+    }
+}
+
+impl ToolOutput for &str {
+    fn into_tool_result(self) -> ToolResult {
+        ToolResult::success("", self)
+    }
+}
+
+impl ToolOutput for Value {
+    fn into_tool_result(self) -> ToolResult {
+        ToolResult::success_json("", self)
+    }
+}
+
+/// Builds a tool input schema field by field; used by the generated code.
+#[cfg(feature = "schemars")]
+#[doc(hidden)]
+pub struct ToolSchemaBuilder {
+    generator: schemars::SchemaGenerator,
+    properties: serde_json::Map<String, Value>,
+    required: Vec<Value>,
+}
+
+#[cfg(feature = "schemars")]
+impl Default for ToolSchemaBuilder {
+    fn default() -> Self {
+        Self {
+            generator: schemars::SchemaGenerator::default(),
+            properties: serde_json::Map::new(),
+            required: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl ToolSchemaBuilder {
+    /// Add a property whose schema is derived from `T`.
+    pub fn field<T: schemars::JsonSchema>(&mut self, name: &str, description: Option<&str>, required: bool) {
+        let mut schema = self.generator.subschema_for::<T>();
+        if let Some(description) = description {
+            schema.insert("description".to_string(), description.into());
+        }
+        self.properties.insert(name.to_string(), schema.to_value());
+        if required {
+            self.required.push(name.into());
+        }
+    }
+
+    /// Finish the tool definition.
+    pub fn build(mut self, name: &str, description: &str) -> Tool {
+        let mut schema = serde_json::Map::new();
+        schema.insert("properties".to_string(), Value::Object(self.properties));
+        schema.insert("required".to_string(), Value::Array(self.required));
+        let definitions = self.generator.take_definitions(true);
+        if !definitions.is_empty() {
+            schema.insert("$defs".to_string(), Value::Object(definitions));
+        }
+        Tool::from_json_schema(name, description, Value::Object(schema))
+    }
+}
@@ -51,6 +51,8 @@
 pub mod executor;
 pub mod registry;
 pub mod conversation;
+pub mod input;
+pub mod typed;
 
 use async_trait::async_trait;
 use serde_json::Value;
@@ -59,6 +61,13 @@ use std::error::Error;
 pub use executor::{ToolExecutor, ToolExecutionConfig, ToolExecutionConfigBuilder};
 pub use registry::{ToolRegistry, SharedToolRegistry};
 pub use conversation::{ToolConversation, ConversationConfig, ConversationConfigBuilder};
+pub use input::{ToolInput, DefinedTool, ToolOutput};
+pub use typed::TypedTool;
+
+/// Derive [`ToolInput`] for a struct, or turn an async function into a [`DefinedTool`]
+/// with `#[tool]`. Requires the `macros` feature.
+#[cfg(feature = "macros")]
+pub use anthropic_sdk_macros::{tool, ToolInput};
 
 use crate::types::ToolResult;
 
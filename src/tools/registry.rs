@@ -1,12 +1,15 @@
 //! Tool registry for managing and executing registered tools.
 
+use serde::Serialize;
 use std::collections::HashMap;
+use std::error::Error;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
 
 use crate::types::{Tool, ToolUse, ToolResult};
-use super::{ToolFunction, ToolError, ToolOperationResult};
+use super::{DefinedTool, ToolFunction, ToolInput, TypedTool, ToolError, ToolOperationResult};
 
 /// Registry for managing tool definitions and their implementations.
 ///
@@ -93,6 +96,29 @@ impl ToolRegistry {
         Ok(())
     }
 
+    /// Register a tool that carries its own definition, e.g. one generated by `#[tool]`.
+    ///
+    /// The tool is registered under the name in its definition.
+    pub fn register_tool(&mut self, tool: impl DefinedTool + 'static) -> ToolOperationResult<()> {
+        let definition = tool.definition();
+        self.register(definition.name.clone(), definition, Box::new(tool))
+    }
+
+    /// Register an async function over a typed input as a tool.
+    ///
+    /// The definition comes from `I`'s [`ToolInput`] impl; the output is sent back as JSON.
+    /// See [`TypedTool`] for building one with another schema.
+    pub fn register_typed<I, O, F, Fut, E>(&mut self, function: F) -> ToolOperationResult<()>
+    where
+        I: ToolInput,
+        O: Serialize + 'static,
+        F: Fn(I) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<O, E>> + Send + 'static,
+        E: Into<Box<dyn Error + Send + Sync>> + 'static,
+    {
+        self.register_tool(TypedTool::from_input(function))
+    }
+
     /// Get tool definitions for all registered tools.
     ///
     /// Returns a vector of tool definitions that can be sent to Claude.
@@ -144,7 +170,9 @@ impl ToolRegistry {
     /// * `tool_use` - Tool use request from Claude
     ///
     /// # Returns
-    /// `ToolResult` with the execution result or error information.
+    /// `ToolResult` with the execution result or error information. Input that
+    /// doesn't match the tool's schema is not executed; the result is an `is_error`
+    /// JSON object listing each violation with its JSON pointer, so Claude can retry.
     ///
     /// # Example
     /// ```rust
@@ -171,10 +199,7 @@ impl ToolRegistry {
 
         // Validate input against schema
         if let Err(validation_error) = tool_entry.definition.validate_input(&tool_use.input) {
-            return Ok(ToolResult::error(
-                tool_use.id.clone(),
-                format!("Validation failed: {}", validation_error),
-            ));
+            return Ok(validation_error.to_tool_result(tool_use.id.clone()));
         }
 
         // Custom validation from the tool implementation
@@ -363,6 +388,37 @@ mod tests {
         assert_eq!(result.tool_use_id, "test_123");
     }
 
+    #[tokio::test]
+    async fn test_invalid_input_returns_violations() {
+        let mut registry = ToolRegistry::new();
+
+        let tool_def = Tool::new("echo", "Echo a message")
+            .parameter("message", "string", "Message to echo")
+            .required("message")
+            .build();
+
+        registry.register("echo", tool_def, Box::new(TestEchoTool)).unwrap();
+
+        let tool_use = ToolUse {
+            id: "test_123".to_string(),
+            name: "echo".to_string(),
+            input: json!({"message": 42}),
+        };
+
+        let result = registry.execute(&tool_use).await.unwrap();
+        assert_eq!(result.tool_use_id, "test_123");
+        assert_eq!(result.is_error, Some(true));
+
+        if let crate::types::ToolResultContent::Json(content) = result.content {
+            assert_eq!(content["error"], "invalid_input");
+            assert_eq!(content["violations"], json!([
+                {"path": "/message", "keyword": "type", "message": "expected string, got integer"}
+            ]));
+        } else {
+            panic!("Expected JSON content");
+        }
+    }
+
     #[tokio::test]
     async fn test_tool_not_found() {
         let registry = ToolRegistry::new();
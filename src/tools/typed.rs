@@ -0,0 +1,279 @@
+//! Tools with typed input and output.
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::future::Future;
+use std::marker::PhantomData;
+
+use crate::types::{Tool, ToolInputSchema, ToolResult};
+use super::{DefinedTool, ToolFunction, ToolInput};
+
+/// Adapts an async function over typed input and output into a [`ToolFunction`].
+///
+/// Input is deserialized into `I`; if that fails, Claude gets an `is_error` result
+/// naming the offending field so it can retry. The output is serialized into
+/// [`ToolResultContent::Json`](crate::types::ToolResultContent::Json). Errors
+/// returned by the function fail the execution, as with any other tool.
+///
+/// # Examples
+/// ```rust
+/// use anthropic_sdk::tools::{ToolRegistry, TypedTool};
+/// use anthropic_sdk::types::Tool;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize)]
+/// struct AddInput { a: i64, b: i64 }
+///
+/// #[derive(Serialize)]
+/// struct AddOutput { sum: i64 }
+///
+/// let definition = Tool::new("add", "Add two numbers")
+///     .parameter("a", "integer", "First operand")
+///     .parameter("b", "integer", "Second operand")
+///     .required("a")
+///     .required("b")
+///     .build();
+///
+/// let add = TypedTool::with_definition(definition, |input: AddInput| async move {
+///     Ok::<_, String>(AddOutput { sum: input.a + input.b })
+/// });
+///
+/// let mut registry = ToolRegistry::new();
+/// registry.register_tool(add).unwrap();
+/// ```
+pub struct TypedTool<I, O, F> {
+    definition: Tool,
+    function: F,
+    _types: PhantomData<fn(I) -> O>,
+}
+
+impl<I, O, F, Fut, E> TypedTool<I, O, F>
+where
+    I: DeserializeOwned + Send + 'static,
+    O: Serialize,
+    F: Fn(I) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<O, E>> + Send,
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    /// Create a typed tool whose schema accepts any object.
+    ///
+    /// Claude only sees the name and description; prefer [`with_definition`](Self::with_definition),
+    /// [`from_input`](Self::from_input) or, with the `schemars` feature, `derive_schema`.
+    pub fn new(name: impl Into<String>, description: impl Into<String>, function: F) -> Self {
+        let definition = Tool {
+            name: name.into(),
+            description: description.into(),
+            input_schema: ToolInputSchema {
+                schema_type: "object".to_string(),
+                properties: Map::new(),
+                required: Vec::new(),
+                additional: Map::new(),
+            },
+            cache_control: None,
+        };
+        Self::with_definition(definition, function)
+    }
+
+    /// Create a typed tool with an explicit definition.
+    pub fn with_definition(definition: Tool, function: F) -> Self {
+        Self { definition, function, _types: PhantomData }
+    }
+
+    /// Replace the input schema with one derived from `I`, keeping the name and description.
+    #[cfg(feature = "schemars")]
+    pub fn derive_schema(mut self) -> Self
+    where
+        I: schemars::JsonSchema,
+    {
+        self.definition.input_schema = Tool::for_type::<I>().input_schema;
+        self
+    }
+}
+
+impl<I, O, F, Fut, E> TypedTool<I, O, F>
+where
+    I: ToolInput,
+    O: Serialize,
+    F: Fn(I) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<O, E>> + Send,
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    /// Create a typed tool using the definition from `I`'s [`ToolInput`] impl.
+    pub fn from_input(function: F) -> Self {
+        Self::with_definition(I::tool(), function)
+    }
+}
+
+#[async_trait]
+impl<I, O, F, Fut, E> ToolFunction for TypedTool<I, O, F>
+where
+    I: DeserializeOwned + Send + 'static,
+    O: Serialize,
+    F: Fn(I) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<O, E>> + Send,
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    async fn execute(&self, input: Value) -> Result<ToolResult, Box<dyn Error + Send + Sync>> {
+        let input: I = match serde_path_to_error::deserialize(input) {
+            Ok(input) => input,
+            Err(error) => {
+                let message = format!(
+                    "Invalid input for tool '{}' at {}: {}",
+                    self.definition.name,
+                    json_pointer(error.path()),
+                    error.inner(),
+                );
+                return Ok(ToolResult::error("", message));
+            }
+        };
+        let output = (self.function)(input).await.map_err(Into::into)?;
+        Ok(ToolResult::success_json("", serde_json::to_value(output)?))
+    }
+}
+
+impl<I, O, F, Fut, E> DefinedTool for TypedTool<I, O, F>
+where
+    I: DeserializeOwned + Send + 'static,
+    O: Serialize,
+    F: Fn(I) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<O, E>> + Send,
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn definition(&self) -> Tool {
+        self.definition.clone()
+    }
+}
+
+/// Render a deserialization path as a JSON pointer, matching schema validation errors.
+fn json_pointer(path: &serde_path_to_error::Path) -> String {
+    use serde_path_to_error::Segment;
+
+    let pointer: String = path
+        .iter()
+        .map(|segment| match segment {
+            Segment::Seq { index } => format!("/{}", index),
+            Segment::Map { key } | Segment::Enum { variant: key } => {
+                format!("/{}", key.replace('~', "~0").replace('/', "~1"))
+            }
+            Segment::Unknown => "/?".to_string(),
+        })
+        .collect();
+    if pointer.is_empty() { "(root)".to_string() } else { pointer }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ToolResultContent;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct Order {
+        items: Vec<Item>,
+    }
+
+    impl ToolInput for Order {
+        fn tool() -> Tool {
+            Tool::from_json_schema("order", "Place an order", json!({
+                "properties": {"items": {"type": "array"}},
+                "required": ["items"],
+            }))
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Item {
+        sku: String,
+        quantity: u32,
+    }
+
+    #[derive(Serialize)]
+    struct Total {
+        skus: Vec<String>,
+        quantity: u32,
+    }
+
+    fn order_tool() -> impl DefinedTool {
+        TypedTool::new("order", "Place an order", |order: Order| async move {
+            if order.items.is_empty() {
+                return Err("order is empty");
+            }
+            Ok(Total {
+                skus: order.items.iter().map(|item| item.sku.clone()).collect(),
+                quantity: order.items.iter().map(|item| item.quantity).sum(),
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_typed_tool_round_trip() {
+        let tool = order_tool();
+        assert_eq!(tool.definition().name, "order");
+
+        let input = json!({"items": [{"sku": "a", "quantity": 2}, {"sku": "b", "quantity": 3}]});
+        let result = tool.execute(input).await.unwrap();
+        assert_eq!(result.is_error, None);
+        assert!(matches!(result.content, ToolResultContent::Json(ref value) if *value == json!({"skus": ["a", "b"], "quantity": 5})));
+
+        assert!(tool.execute(json!({"items": []})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_typed_tool_input_errors_name_the_field() {
+        let result = order_tool()
+            .execute(json!({"items": [{"sku": "a", "quantity": 1}, {"sku": "b", "quantity": -1}]}))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        let ToolResultContent::Text(message) = result.content else {
+            panic!("Expected text content");
+        };
+        assert!(message.starts_with("Invalid input for tool 'order' at /items/1/quantity: "), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_register_typed() {
+        let mut registry = super::super::ToolRegistry::new();
+        registry
+            .register_typed(|order: Order| async move {
+                Ok::<_, String>(Total { skus: Vec::new(), quantity: order.items.len() as u32 })
+            })
+            .unwrap();
+        assert_eq!(registry.get_tool_definition("order").unwrap().input_schema.required, vec!["items".to_string()]);
+
+        let tool_use = crate::types::ToolUse {
+            id: "toolu_1".to_string(),
+            name: "order".to_string(),
+            input: json!({"items": [{"sku": "a", "quantity": 1}]}),
+        };
+        let result = registry.execute(&tool_use).await.unwrap();
+        assert_eq!(result.tool_use_id, "toolu_1");
+        assert!(matches!(result.content, ToolResultContent::Json(ref value) if value["quantity"] == 1));
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_derive_schema() {
+        /// Search input.
+        #[derive(Deserialize, schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Search {
+            query: String,
+            limit: Option<u32>,
+        }
+
+        let tool = TypedTool::new("search", "Search the index", |search: Search| async move {
+            Ok::<_, String>(vec![search.query])
+        })
+        .derive_schema();
+        let definition = tool.definition();
+        assert_eq!(definition.name, "search");
+        assert_eq!(definition.description, "Search the index");
+        assert_eq!(definition.input_schema.required, vec!["query".to_string()]);
+        assert!(definition.input_schema.properties.contains_key("limit"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::types::{Message, MessageCreateParams};
+use crate::types::{Message, MessageCreateParams, SystemPrompt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
@@ -43,7 +43,7 @@ pub struct MessageBatch {
 }
 
 /// Status of batch processing
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum BatchStatus {
     /// Batch is validating inputs
@@ -69,6 +69,10 @@ pub enum BatchStatus {
     
     /// Batch processing failed
     Failed,
+    
+    /// A status not known to this version of the SDK
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl BatchStatus {
@@ -159,6 +163,11 @@ impl BatchRequest {
                 tools: None,
                 tool_choice: None,
                 metadata: None,
+                thinking: None,
+                service_tier: None,
+                container: None,
+                mcp_servers: None,
+                context_management: None,
             },
         }
     }
@@ -197,7 +206,7 @@ impl BatchRequestBuilder {
     }
     
     /// Set the system prompt for the request
-    pub fn system(mut self, system: impl Into<String>) -> Self {
+    pub fn system(mut self, system: impl Into<SystemPrompt>) -> Self {
         self.body.system = Some(system.into());
         self
     }
@@ -227,8 +236,8 @@ impl BatchRequestBuilder {
     }
     
     /// Add tools to the request
-    pub fn tools(mut self, tools: Vec<crate::types::Tool>) -> Self {
-        self.body.tools = Some(tools);
+    pub fn tools<T: Into<crate::types::ToolUnion>>(mut self, tools: Vec<T>) -> Self {
+        self.body.tools = Some(tools.into_iter().map(Into::into).collect());
         self
     }
     
@@ -239,8 +248,8 @@ impl BatchRequestBuilder {
     }
     
     /// Add metadata to the request
-    pub fn metadata(mut self, metadata: HashMap<String, String>) -> Self {
-        self.body.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<crate::types::Metadata>) -> Self {
+        self.body.metadata = Some(metadata.into());
         self
     }
     
@@ -433,6 +442,17 @@ mod tests {
         assert!(BatchStatus::InProgress.is_processing());
     }
 
+    #[test]
+    fn test_unknown_batch_status() {
+        let status: BatchStatus = serde_json::from_str(r#""archived""#).unwrap();
+        assert_eq!(status, BatchStatus::Other("archived".to_string()));
+        assert!(!status.is_terminal());
+        assert_eq!(serde_json::to_string(&status).unwrap(), r#""archived""#);
+
+        let known: BatchStatus = serde_json::from_str(r#""in_progress""#).unwrap();
+        assert_eq!(known, BatchStatus::InProgress);
+    }
+
     #[test]
     fn test_batch_request_builder() {
         let request = BatchRequest::new("test1", "claude-3-5-sonnet-latest", 1024)
@@ -447,7 +467,7 @@ mod tests {
         assert_eq!(request.body.model, "claude-3-5-sonnet-latest");
         assert_eq!(request.body.max_tokens, 1024);
         assert_eq!(request.body.messages.len(), 1);
-        assert_eq!(request.body.system, Some("You are a helpful assistant".to_string()));
+        assert_eq!(request.body.system, Some(SystemPrompt::from("You are a helpful assistant")));
         assert_eq!(request.body.temperature, Some(0.7));
     }
 
@@ -53,6 +53,12 @@ pub enum AnthropicError {
     #[error("Service unavailable: {message}")]
     ServiceUnavailable { message: String },
     
+    #[error("Structured output did not match the schema after {attempts} attempts: {message}")]
+    StructuredOutput { message: String, attempts: u32 },
+    
+    #[error("Invalid request: {0}")]
+    InvalidRequest(#[from] crate::types::validation::RequestValidationError),
+    
     #[error("{0}")]
     Other(String),
 }
@@ -3,6 +3,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 
+/// Beta flag required to reference uploaded files in message content
+pub const FILES_API_BETA: &str = "files-api-2025-04-14";
+
 /// File object from the Anthropic Files API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileObject {
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
-use crate::types::shared::{RequestId, Usage};
+use crate::types::shared::{CacheControl, RequestId, Usage};
 use crate::files::{File, FileError};
+use crate::types::files_api::FileObject;
+use crate::types::tools::{ToolUse, WebSearchToolResultContent};
 
 /// A message from Claude
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -35,6 +37,80 @@ pub struct Message {
     pub request_id: Option<RequestId>,
 }
 
+impl Message {
+    /// The text of all text blocks, concatenated
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+    
+    /// The client tool calls Claude asked for, in order
+    pub fn tool_uses(&self) -> Vec<ToolUse> {
+        self.content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::ToolUse { id, name, input } => Some(ToolUse {
+                    id: id.clone(),
+                    name: name.clone(),
+                    input: input.clone(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+    
+    /// The extended thinking text, concatenated, or `None` if Claude didn't think out loud
+    pub fn thinking(&self) -> Option<String> {
+        let mut thinking_blocks = self.content.iter().filter_map(|block| match block {
+            ContentBlock::Thinking { thinking, .. } => Some(thinking.as_str()),
+            _ => None,
+        });
+        let first = thinking_blocks.next()?;
+        Some(thinking_blocks.fold(first.to_string(), |text, block| text + block))
+    }
+    
+    /// Convert the response into a message for the next request's history
+    ///
+    /// Every block is kept as-is, including thinking signatures that must be
+    /// sent back unchanged when continuing a tool use turn.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use anthropic_sdk::{Anthropic, MessageCreateBuilder, MessageParam, Role};
+    ///
+    /// let client = Anthropic::from_env()?;
+    /// let mut params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100)
+    ///     .user("Name a color")
+    ///     .build();
+    /// let reply = client.messages().create(params.clone()).await?;
+    /// println!("{}", reply.text());
+    ///
+    /// params.messages.push(reply.into_param());
+    /// params.messages.push(MessageParam { role: Role::User, content: "Another one".into() });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_param(self) -> MessageParam {
+        MessageParam {
+            role: self.role,
+            content: MessageContent::Blocks(self.content.into_iter().map(ContentBlockParam::from).collect()),
+        }
+    }
+}
+
+impl From<Message> for MessageParam {
+    fn from(message: Message) -> Self {
+        message.into_param()
+    }
+}
+
 /// Conversational role
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -48,7 +124,12 @@ pub enum Role {
 #[serde(tag = "type")]
 pub enum ContentBlock {
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        /// Sources supporting this text, when citations are enabled on a document
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        citations: Option<Vec<TextCitation>>,
+    },
     
     #[serde(rename = "image")]
     Image { source: ImageSource },
@@ -66,6 +147,182 @@ pub enum ContentBlock {
         content: Option<String>,
         is_error: Option<bool>,
     },
+    
+    /// Extended thinking produced before the final answer
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    
+    /// Thinking that was encrypted by the safety systems
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
+    
+    /// A call to a server tool, executed by Anthropic
+    #[serde(rename = "server_tool_use")]
+    ServerToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    
+    /// Results of a web search server tool call
+    #[serde(rename = "web_search_tool_result")]
+    WebSearchToolResult {
+        tool_use_id: String,
+        content: WebSearchToolResultContent,
+    },
+    
+    /// A block type not known to this version of the SDK, kept as raw JSON
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+/// Citation information for text blocks.
+///
+/// Citations provide source attribution for generated text,
+/// with different types depending on the source document.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum TextCitation {
+    /// Citation pointing to character locations in plain text.
+    #[serde(rename = "char_location")]
+    CharLocation {
+        /// The text being cited
+        cited_text: String,
+        /// Index of the source document
+        document_index: usize,
+        /// Title of the source document (may be null)
+        document_title: Option<String>,
+        /// Starting character index
+        start_char_index: usize,
+        /// Ending character index
+        end_char_index: usize,
+    },
+
+    /// Citation pointing to page locations in PDFs.
+    #[serde(rename = "page_location")]
+    PageLocation {
+        /// The text being cited
+        cited_text: String,
+        /// Index of the source document
+        document_index: usize,
+        /// Title of the source document (may be null)
+        document_title: Option<String>,
+        /// Starting page number
+        start_page_number: usize,
+        /// Ending page number
+        end_page_number: usize,
+    },
+
+    /// Citation pointing to content block locations.
+    #[serde(rename = "content_block_location")]
+    ContentBlockLocation {
+        /// The text being cited
+        cited_text: String,
+        /// Index of the source document
+        document_index: usize,
+        /// Title of the source document (may be null)
+        document_title: Option<String>,
+        /// Starting content block index
+        start_block_index: usize,
+        /// Ending content block index
+        end_block_index: usize,
+    },
+
+    /// Citation pointing to web search results.
+    #[serde(rename = "web_search_result_location")]
+    WebSearchResultLocation {
+        /// The text being cited
+        cited_text: String,
+        /// Encrypted index for the search result
+        encrypted_index: String,
+        /// Title of the web page (may be null)
+        title: Option<String>,
+        /// URL of the web page
+        url: String,
+    },
+}
+
+impl TextCitation {
+    /// The text being cited
+    pub fn cited_text(&self) -> &str {
+        match self {
+            TextCitation::CharLocation { cited_text, .. }
+            | TextCitation::PageLocation { cited_text, .. }
+            | TextCitation::ContentBlockLocation { cited_text, .. }
+            | TextCitation::WebSearchResultLocation { cited_text, .. } => cited_text,
+        }
+    }
+    
+    /// Index of the cited document among all documents in the request
+    pub fn document_index(&self) -> Option<usize> {
+        match self {
+            TextCitation::CharLocation { document_index, .. }
+            | TextCitation::PageLocation { document_index, .. }
+            | TextCitation::ContentBlockLocation { document_index, .. } => Some(*document_index),
+            TextCitation::WebSearchResultLocation { .. } => None,
+        }
+    }
+    
+    /// Resolve the citation to the span of the request document it points to
+    ///
+    /// Returns `None` for web search citations, or if the request has no
+    /// matching document or the location is out of range.
+    pub fn resolve<'a>(&self, params: &'a MessageCreateParams) -> Option<CitedSource<'a>> {
+        let document = *params.documents().get(self.document_index()?)?;
+        let ContentBlockParam::Document { source, .. } = document else {
+            return None;
+        };
+        
+        match (self, source) {
+            (
+                TextCitation::CharLocation { start_char_index, end_char_index, .. },
+                DocumentSource::Text { data, .. },
+            ) => char_slice(data, *start_char_index, *end_char_index).map(CitedSource::Text),
+            (TextCitation::PageLocation { start_page_number, end_page_number, .. }, _) => Some(CitedSource::Pages {
+                document,
+                start_page: *start_page_number,
+                end_page: *end_page_number,
+            }),
+            (
+                TextCitation::ContentBlockLocation { start_block_index, end_block_index, .. },
+                DocumentSource::Content { content },
+            ) => match content {
+                MessageContent::Blocks(blocks) => blocks.get(*start_block_index..*end_block_index).map(CitedSource::Blocks),
+                MessageContent::Text(text) if *start_block_index == 0 && *end_block_index == 1 => {
+                    Some(CitedSource::Text(text))
+                }
+                MessageContent::Text(_) => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The part of a request document that a citation points to
+#[derive(Debug, Clone, PartialEq)]
+pub enum CitedSource<'a> {
+    /// Character span of a plain text document
+    Text(&'a str),
+    /// Page range of a PDF document, 1-indexed with an exclusive end
+    Pages {
+        document: &'a ContentBlockParam,
+        start_page: usize,
+        end_page: usize,
+    },
+    /// Blocks of a custom content document
+    Blocks(&'a [ContentBlockParam]),
+}
+
+/// Slice `text` by character indices, with an exclusive end.
+fn char_slice(text: &str, start: usize, end: usize) -> Option<&str> {
+    let byte_index = |index: usize| {
+        text.char_indices().map(|(i, _)| i).chain(std::iter::once(text.len())).nth(index)
+    };
+    let (start, end) = (byte_index(start)?, byte_index(end)?);
+    text.get(start..end)
 }
 
 /// Image source for image content blocks
@@ -82,6 +339,12 @@ pub enum ImageSource {
     Url {
         url: String,
     },
+    
+    /// Image uploaded through the Files API
+    #[serde(rename = "file")]
+    File {
+        file_id: String,
+    },
 }
 
 /// Reasons why the model stopped generating
@@ -92,6 +355,42 @@ pub enum StopReason {
     MaxTokens,
     StopSequence,
     ToolUse,
+    
+    /// A long-running server tool paused the turn; send the response back to continue it
+    PauseTurn,
+    
+    /// The model declined to respond for safety reasons
+    Refusal,
+    
+    /// The conversation no longer fits in the model's context window
+    ModelContextWindowExceeded,
+    
+    /// A stop reason not known to this version of the SDK
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// Configuration for extended thinking
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ThinkingConfig {
+    /// Let the model think before answering, using up to `budget_tokens`
+    Enabled { budget_tokens: u32 },
+    
+    /// Disable extended thinking
+    Disabled,
+}
+
+impl ThinkingConfig {
+    /// Enable thinking with the given token budget
+    pub fn enabled(budget_tokens: u32) -> Self {
+        Self::Enabled { budget_tokens }
+    }
+    
+    /// Disable thinking
+    pub fn disabled() -> Self {
+        Self::Disabled
+    }
 }
 
 /// Parameters for creating a new message
@@ -108,7 +407,7 @@ pub struct MessageCreateParams {
     
     /// System prompt (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub system: Option<String>,
+    pub system: Option<SystemPrompt>,
     
     /// Amount of randomness (0.0 to 1.0)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -132,15 +431,454 @@ pub struct MessageCreateParams {
     
     /// Tools available for the model to use
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tools: Option<Vec<crate::types::Tool>>,
+    pub tools: Option<Vec<crate::types::ToolUnion>>,
+    
+    /// Tool choice strategy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<crate::types::ToolChoice>,
+    
+    /// Request metadata, such as an opaque end-user identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+    
+    /// Extended thinking configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<ThinkingConfig>,
+    
+    /// Whether the request may use priority capacity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<ServiceTierPreference>,
+    
+    /// Container to reuse for code execution across requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+    
+    /// Remote MCP servers whose tools the model may call (beta)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<Vec<McpServer>>,
+    
+    /// Automatic clearing of old context as the conversation grows (beta)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_management: Option<ContextManagement>,
+}
+
+/// Beta flag required to connect to MCP servers
+pub const MCP_CLIENT_BETA: &str = "mcp-client-2025-04-04";
+
+/// Beta flag required for context management
+pub const CONTEXT_MANAGEMENT_BETA: &str = "context-management-2025-06-27";
+
+/// Limits on continuing a response that stopped at `max_tokens`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContinuationLimits {
+    /// Maximum number of extra requests
+    pub max_continuations: u32,
+
+    /// Maximum output tokens across all requests, if any
+    pub max_output_tokens: Option<u32>,
+}
+
+impl ContinuationLimits {
+    /// Allow up to `max_continuations` extra requests
+    pub fn new(max_continuations: u32) -> Self {
+        Self { max_continuations, max_output_tokens: None }
+    }
+
+    /// Stop once this many output tokens have been generated in total
+    pub fn max_output_tokens(mut self, max_output_tokens: u32) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+}
+
+impl Default for ContinuationLimits {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+/// Metadata describing the request
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Metadata {
+    /// Opaque identifier for the end user, e.g. a hash or UUID; never an email or name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
+
+impl Metadata {
+    /// Metadata identifying the end user
+    pub fn user_id(user_id: impl Into<String>) -> Self {
+        Self { user_id: Some(user_id.into()) }
+    }
+}
+
+impl From<std::collections::HashMap<String, String>> for Metadata {
+    /// Build metadata from a map, keeping the `user_id` key
+    fn from(mut map: std::collections::HashMap<String, String>) -> Self {
+        Self { user_id: map.remove("user_id") }
+    }
+}
+
+/// Which capacity a request may be served from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceTierPreference {
+    /// Use priority capacity when available, falling back to standard
+    Auto,
+    
+    /// Only use standard capacity
+    StandardOnly,
+}
+
+/// A remote MCP server made available to the model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpServer {
+    /// Server type; always `url`
+    #[serde(rename = "type")]
+    pub server_type: String,
+    
+    /// URL of the server
+    pub url: String,
+    
+    /// Name used to identify the server's tools
+    pub name: String,
+    
+    /// OAuth bearer token sent to the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization_token: Option<String>,
+    
+    /// Which of the server's tools may be used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_configuration: Option<McpToolConfiguration>,
+}
+
+/// Tool restrictions for an MCP server
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct McpToolConfiguration {
+    /// Whether the server's tools are enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    
+    /// Only allow these tools
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+impl McpServer {
+    /// A URL-based MCP server
+    pub fn url(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            server_type: "url".to_string(),
+            url: url.into(),
+            name: name.into(),
+            authorization_token: None,
+            tool_configuration: None,
+        }
+    }
+    
+    /// Authenticate with an OAuth bearer token
+    pub fn authorization_token(mut self, token: impl Into<String>) -> Self {
+        self.authorization_token = Some(token.into());
+        self
+    }
+    
+    /// Only allow the given tools from this server
+    pub fn allowed_tools<I, S>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tool_configuration.get_or_insert_with(McpToolConfiguration::default).allowed_tools =
+            Some(tools.into_iter().map(Into::into).collect());
+        self
+    }
+    
+    /// Enable or disable all of this server's tools
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.tool_configuration.get_or_insert_with(McpToolConfiguration::default).enabled = Some(enabled);
+        self
+    }
+}
+
+/// Context management configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContextManagement {
+    /// Edits applied to the context before it reaches the model
+    pub edits: Vec<ContextEdit>,
+}
+
+impl ContextManagement {
+    /// Context management with a single edit
+    pub fn new(edit: ContextEdit) -> Self {
+        Self { edits: vec![edit] }
+    }
+    
+    /// Add another edit
+    pub fn edit(mut self, edit: ContextEdit) -> Self {
+        self.edits.push(edit);
+        self
+    }
+}
+
+/// A context editing strategy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum ContextEdit {
+    /// Clear old tool results once the context grows past a threshold
+    #[serde(rename = "clear_tool_uses_20250919")]
+    ClearToolUses {
+        /// When to start clearing; the API defaults to 100k input tokens
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trigger: Option<ContextThreshold>,
+        /// How many recent tool uses to keep
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        keep: Option<ContextThreshold>,
+        /// Minimum amount to clear each time, so cache invalidation is worthwhile
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clear_at_least: Option<ContextThreshold>,
+        /// Tools whose results are never cleared
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        exclude_tools: Option<Vec<String>>,
+        /// Also clear the tool call inputs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clear_tool_inputs: Option<bool>,
+    },
+}
+
+impl ContextEdit {
+    /// Clear tool uses with the API defaults
+    pub fn clear_tool_uses() -> Self {
+        Self::ClearToolUses {
+            trigger: None,
+            keep: None,
+            clear_at_least: None,
+            exclude_tools: None,
+            clear_tool_inputs: None,
+        }
+    }
+}
+
+/// A threshold measured in input tokens or tool uses
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContextThreshold {
+    /// A number of input tokens
+    InputTokens { value: u32 },
+    
+    /// A number of tool uses
+    ToolUses { value: u32 },
+}
+
+impl MessageCreateParams {
+    /// Beta flags the request needs based on its content
+    pub(crate) fn required_betas(&self) -> Vec<&'static str> {
+        required_betas(&self.messages, self.mcp_servers.as_deref(), self.context_management.as_ref())
+    }
+    
+    /// Document blocks in the request, in the order used by citation indices
+    ///
+    /// Includes documents returned inside tool results.
+    pub fn documents(&self) -> Vec<&ContentBlockParam> {
+        let mut documents = Vec::new();
+        for message in &self.messages {
+            collect_documents(&message.content, &mut documents);
+        }
+        documents
+    }
+}
+
+/// Beta flags needed to send the given messages
+fn required_betas(
+    messages: &[MessageParam],
+    mcp_servers: Option<&[McpServer]>,
+    context_management: Option<&ContextManagement>,
+) -> Vec<&'static str> {
+    let mut betas = Vec::new();
+    if messages.iter().any(|message| uses_files(&message.content)) {
+        betas.push(crate::types::files_api::FILES_API_BETA);
+    }
+    if mcp_servers.is_some_and(|servers| !servers.is_empty()) {
+        betas.push(MCP_CLIENT_BETA);
+    }
+    if context_management.is_some() {
+        betas.push(CONTEXT_MANAGEMENT_BETA);
+    }
+    betas
+}
+
+/// Append the document blocks in content, including inside tool results
+fn collect_documents<'a>(content: &'a MessageContent, documents: &mut Vec<&'a ContentBlockParam>) {
+    let MessageContent::Blocks(blocks) = content else {
+        return;
+    };
+    for block in blocks {
+        match block {
+            ContentBlockParam::Document { .. } => documents.push(block),
+            ContentBlockParam::ToolResult { content: Some(content), .. } => collect_documents(content, documents),
+            _ => {}
+        }
+    }
+}
+
+/// Whether content refers to an uploaded file, including inside tool results and documents
+fn uses_files(content: &MessageContent) -> bool {
+    let MessageContent::Blocks(blocks) = content else {
+        return false;
+    };
+    blocks.iter().any(|block| match block {
+        ContentBlockParam::Image { source: ImageSource::File { .. }, .. }
+        | ContentBlockParam::Document { source: DocumentSource::File { .. }, .. } => true,
+        ContentBlockParam::Document { source: DocumentSource::Content { content }, .. } => uses_files(content),
+        ContentBlockParam::ToolResult { content: Some(content), .. } => uses_files(content),
+        _ => false,
+    })
+}
+
+/// Parameters for counting the input tokens of a message request
+///
+/// Usually built from [`MessageCreateParams`], dropping the fields that only
+/// affect generation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageCountTokensParams {
+    /// The model whose tokenizer to use
+    pub model: String,
+    
+    /// Input messages for the conversation
+    pub messages: Vec<MessageParam>,
+    
+    /// System prompt (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<SystemPrompt>,
+    
+    /// Tools available for the model to use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<crate::types::ToolUnion>>,
     
     /// Tool choice strategy
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<crate::types::ToolChoice>,
     
-    /// Additional metadata
+    /// Extended thinking configuration
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<std::collections::HashMap<String, String>>,
+    pub thinking: Option<ThinkingConfig>,
+    
+    /// Remote MCP servers whose tool definitions count towards the input (beta)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<Vec<McpServer>>,
+    
+    /// Automatic clearing of old context (beta)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_management: Option<ContextManagement>,
+}
+
+impl MessageCountTokensParams {
+    /// Beta flags the request needs based on its content
+    pub(crate) fn required_betas(&self) -> Vec<&'static str> {
+        required_betas(&self.messages, self.mcp_servers.as_deref(), self.context_management.as_ref())
+    }
+}
+
+impl From<MessageCreateParams> for MessageCountTokensParams {
+    fn from(params: MessageCreateParams) -> Self {
+        Self {
+            model: params.model,
+            messages: params.messages,
+            system: params.system,
+            tools: params.tools,
+            tool_choice: params.tool_choice,
+            thinking: params.thinking,
+            mcp_servers: params.mcp_servers,
+            context_management: params.context_management,
+        }
+    }
+}
+
+impl From<&MessageCreateParams> for MessageCountTokensParams {
+    fn from(params: &MessageCreateParams) -> Self {
+        params.clone().into()
+    }
+}
+
+/// Result of counting the tokens of a message request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MessageTokensCount {
+    /// Total input tokens across messages, system prompt and tools
+    pub input_tokens: u32,
+}
+
+impl MessageTokensCount {
+    /// Cost in USD of sending these input tokens at the given pricing
+    pub fn input_cost(&self, pricing: &crate::types::ModelPricing) -> f64 {
+        self.input_tokens as f64 * pricing.input_price_per_million / 1_000_000.0
+    }
+}
+
+/// System prompt, either plain text or a list of text blocks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum SystemPrompt {
+    /// Plain text system prompt
+    Text(String),
+    /// Text blocks, which can carry cache breakpoints
+    Blocks(Vec<SystemBlock>),
+}
+
+impl SystemPrompt {
+    /// The prompt text, with blocks joined by newlines
+    pub fn to_text(&self) -> String {
+        match self {
+            SystemPrompt::Text(text) => text.clone(),
+            SystemPrompt::Blocks(blocks) => blocks
+                .iter()
+                .map(|SystemBlock::Text { text, .. }| text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+impl From<String> for SystemPrompt {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for SystemPrompt {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<Vec<SystemBlock>> for SystemPrompt {
+    fn from(blocks: Vec<SystemBlock>) -> Self {
+        Self::Blocks(blocks)
+    }
+}
+
+/// A block in a system prompt
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum SystemBlock {
+    #[serde(rename = "text")]
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+}
+
+impl SystemBlock {
+    /// Create a text block
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into(), cache_control: None }
+    }
+    
+    /// Mark this block as a cache breakpoint
+    pub fn cache_control(self, cache_control: CacheControl) -> Self {
+        match self {
+            Self::Text { text, .. } => Self::Text { text, cache_control: Some(cache_control) },
+        }
+    }
 }
 
 /// A single message in the conversation
@@ -151,7 +889,7 @@ pub struct MessageParam {
 }
 
 /// Content for a message parameter  
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum MessageContent {
     /// Simple text content
@@ -161,28 +899,130 @@ pub enum MessageContent {
 }
 
 /// Content block parameters for input messages
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum ContentBlockParam {
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        citations: Option<Vec<TextCitation>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
     
     #[serde(rename = "image")]
-    Image { source: ImageSource },
+    Image {
+        source: ImageSource,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
     
     #[serde(rename = "tool_use")]
     ToolUse {
         id: String,
         name: String,
         input: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     
+    /// Result of a tool call; content is text or a list of text, image and document blocks
     #[serde(rename = "tool_result")]
     ToolResult {
         tool_use_id: String,
-        content: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<MessageContent>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         is_error: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
+    
+    /// A PDF or text document
+    #[serde(rename = "document")]
+    Document {
+        source: DocumentSource,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        context: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        citations: Option<CitationsConfig>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    
+    /// Extended thinking produced before the final answer
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    
+    /// Thinking that was encrypted by the safety systems
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
+    
+    /// A server tool call from an earlier assistant turn
+    #[serde(rename = "server_tool_use")]
+    ServerToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    
+    /// Web search results from an earlier assistant turn
+    #[serde(rename = "web_search_tool_result")]
+    WebSearchToolResult {
+        tool_use_id: String,
+        content: WebSearchToolResultContent,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    
+    /// A block type not known to this version of the SDK, sent back as raw JSON
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+/// Source of a document content block
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum DocumentSource {
+    /// Base64-encoded PDF
+    #[serde(rename = "base64")]
+    Base64 {
+        media_type: String,
+        data: String,
+    },
+    
+    /// PDF fetched from a URL
+    #[serde(rename = "url")]
+    Url { url: String },
+    
+    /// Plain text document
+    #[serde(rename = "text")]
+    Text {
+        media_type: String,
+        data: String,
+    },
+    
+    /// Custom content, cited per block instead of per sentence
+    #[serde(rename = "content")]
+    Content { content: MessageContent },
+    
+    /// PDF or text document uploaded through the Files API
+    #[serde(rename = "file")]
+    File { file_id: String },
+}
+
+/// Whether the model may cite a document in its answer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CitationsConfig {
+    pub enabled: bool,
 }
 
 /// Builder for creating message requests ergonomically
@@ -208,6 +1048,11 @@ impl MessageCreateBuilder {
                 tools: None,
                 tool_choice: None,
                 metadata: None,
+                thinking: None,
+                service_tier: None,
+                container: None,
+                mcp_servers: None,
+                context_management: None,
             },
         }
     }
@@ -232,11 +1077,44 @@ impl MessageCreateBuilder {
     }
     
     /// Set the system prompt
-    pub fn system(mut self, system: impl Into<String>) -> Self {
+    pub fn system(mut self, system: impl Into<SystemPrompt>) -> Self {
         self.params.system = Some(system.into());
         self
     }
     
+    /// Set the system prompt as a single block marked as a cache breakpoint
+    pub fn system_cached(self, system: impl Into<String>) -> Self {
+        self.system(vec![SystemBlock::text(system).cache_control(CacheControl::ephemeral())])
+    }
+    
+    /// Mark the last tool definition as a cache breakpoint, caching all tools
+    pub fn cache_tools(mut self) -> Self {
+        if let Some(tools) = self.params.tools.as_mut() {
+            if let Some(tool) = tools.pop() {
+                tools.push(tool.cache_control(CacheControl::ephemeral()));
+            }
+        }
+        self
+    }
+    
+    /// Mark the last block of the last message as a cache breakpoint
+    ///
+    /// Text content is converted to a single text block so it can carry the marker.
+    pub fn cache_last_message(mut self) -> Self {
+        if let Some(message) = self.params.messages.last_mut() {
+            message.content = match std::mem::replace(&mut message.content, MessageContent::Blocks(Vec::new())) {
+                MessageContent::Text(text) => MessageContent::Blocks(vec![ContentBlockParam::text(text)]),
+                blocks => blocks,
+            };
+            if let MessageContent::Blocks(blocks) = &mut message.content {
+                if let Some(block) = blocks.pop() {
+                    blocks.push(block.cache_control(CacheControl::ephemeral()));
+                }
+            }
+        }
+        self
+    }
+    
     /// Set the temperature
     pub fn temperature(mut self, temperature: f32) -> Self {
         self.params.temperature = Some(temperature);
@@ -268,8 +1146,17 @@ impl MessageCreateBuilder {
     }
     
     /// Set tools available for the model to use
-    pub fn tools(mut self, tools: Vec<crate::types::Tool>) -> Self {
-        self.params.tools = Some(tools);
+    ///
+    /// Accepts custom [`Tool`](crate::types::Tool)s, [`ServerTool`](crate::types::ServerTool)s
+    /// or a mix of both as [`ToolUnion`](crate::types::ToolUnion)s.
+    pub fn tools<T: Into<crate::types::ToolUnion>>(mut self, tools: Vec<T>) -> Self {
+        self.params.tools = Some(tools.into_iter().map(Into::into).collect());
+        self
+    }
+    
+    /// Add a single custom or server tool
+    pub fn tool(mut self, tool: impl Into<crate::types::ToolUnion>) -> Self {
+        self.params.tools.get_or_insert_with(Vec::new).push(tool.into());
         self
     }
     
@@ -280,11 +1167,51 @@ impl MessageCreateBuilder {
     }
     
     /// Set metadata
-    pub fn metadata(mut self, metadata: std::collections::HashMap<String, String>) -> Self {
-        self.params.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<Metadata>) -> Self {
+        self.params.metadata = Some(metadata.into());
+        self
+    }
+    
+    /// Identify the end user for abuse detection
+    pub fn user_id(self, user_id: impl Into<String>) -> Self {
+        self.metadata(Metadata::user_id(user_id))
+    }
+    
+    /// Choose whether the request may use priority capacity
+    pub fn service_tier(mut self, service_tier: ServiceTierPreference) -> Self {
+        self.params.service_tier = Some(service_tier);
+        self
+    }
+    
+    /// Reuse a code execution container from an earlier response
+    pub fn container(mut self, container_id: impl Into<String>) -> Self {
+        self.params.container = Some(container_id.into());
+        self
+    }
+    
+    /// Connect a remote MCP server
+    pub fn mcp_server(mut self, server: McpServer) -> Self {
+        self.params.mcp_servers.get_or_insert_with(Vec::new).push(server);
+        self
+    }
+    
+    /// Configure automatic context management
+    pub fn context_management(mut self, context_management: ContextManagement) -> Self {
+        self.params.context_management = Some(context_management);
         self
     }
     
+    /// Set the extended thinking configuration
+    pub fn thinking(mut self, thinking: ThinkingConfig) -> Self {
+        self.params.thinking = Some(thinking);
+        self
+    }
+    
+    /// Enable extended thinking with the given token budget
+    pub fn enable_thinking(self, budget_tokens: u32) -> Self {
+        self.thinking(ThinkingConfig::enabled(budget_tokens))
+    }
+    
     /// Build the message creation parameters
     pub fn build(self) -> MessageCreateParams {
         self.params
@@ -310,11 +1237,76 @@ impl From<Vec<ContentBlockParam>> for MessageContent {
     }
 }
 
+/// Send a response block back in a request, preserving it unmodified
+impl From<ContentBlock> for ContentBlockParam {
+    fn from(block: ContentBlock) -> Self {
+        match block {
+            ContentBlock::Text { text, citations } => ContentBlockParam::Text { text, citations, cache_control: None },
+            ContentBlock::Image { source } => ContentBlockParam::Image { source, cache_control: None },
+            ContentBlock::ToolUse { id, name, input } => {
+                ContentBlockParam::ToolUse { id, name, input, cache_control: None }
+            }
+            ContentBlock::ToolResult { tool_use_id, content, is_error } => {
+                ContentBlockParam::ToolResult {
+                    tool_use_id,
+                    content: content.map(MessageContent::Text),
+                    is_error,
+                    cache_control: None,
+                }
+            }
+            ContentBlock::Thinking { thinking, signature } => ContentBlockParam::Thinking { thinking, signature },
+            ContentBlock::RedactedThinking { data } => ContentBlockParam::RedactedThinking { data },
+            ContentBlock::ServerToolUse { id, name, input } => {
+                ContentBlockParam::ServerToolUse { id, name, input, cache_control: None }
+            }
+            ContentBlock::WebSearchToolResult { tool_use_id, content } => {
+                ContentBlockParam::WebSearchToolResult { tool_use_id, content, cache_control: None }
+            }
+            ContentBlock::Unknown(value) => ContentBlockParam::Unknown(value),
+        }
+    }
+}
+
+impl From<&ContentBlock> for ContentBlockParam {
+    fn from(block: &ContentBlock) -> Self {
+        block.clone().into()
+    }
+}
+
+/// View a request block as a response block
+///
+/// `cache_control` is dropped. Blocks with no response counterpart, such as
+/// documents or tool results with block content, become [`ContentBlock::Unknown`]
+/// holding their whole JSON, so converting back yields the same request.
+impl From<ContentBlockParam> for ContentBlock {
+    fn from(block: ContentBlockParam) -> Self {
+        match block {
+            ContentBlockParam::Text { text, citations, .. } => ContentBlock::Text { text, citations },
+            ContentBlockParam::Image { source, .. } => ContentBlock::Image { source },
+            ContentBlockParam::ToolUse { id, name, input, .. } => ContentBlock::ToolUse { id, name, input },
+            ContentBlockParam::ToolResult { tool_use_id, content: None, is_error, .. } => {
+                ContentBlock::ToolResult { tool_use_id, content: None, is_error }
+            }
+            ContentBlockParam::ToolResult { tool_use_id, content: Some(MessageContent::Text(text)), is_error, .. } => {
+                ContentBlock::ToolResult { tool_use_id, content: Some(text), is_error }
+            }
+            ContentBlockParam::Thinking { thinking, signature } => ContentBlock::Thinking { thinking, signature },
+            ContentBlockParam::RedactedThinking { data } => ContentBlock::RedactedThinking { data },
+            ContentBlockParam::ServerToolUse { id, name, input, .. } => ContentBlock::ServerToolUse { id, name, input },
+            ContentBlockParam::WebSearchToolResult { tool_use_id, content, .. } => {
+                ContentBlock::WebSearchToolResult { tool_use_id, content }
+            }
+            ContentBlockParam::Unknown(value) => ContentBlock::Unknown(value),
+            other => ContentBlock::Unknown(serde_json::to_value(other).unwrap_or_default()),
+        }
+    }
+}
+
 // Helper constructors for ContentBlockParam
 impl ContentBlockParam {
     /// Create a text content block
     pub fn text(text: impl Into<String>) -> Self {
-        Self::Text { text: text.into() }
+        Self::Text { text: text.into(), citations: None, cache_control: None }
     }
     
     /// Create an image content block from base64 data
@@ -324,6 +1316,7 @@ impl ContentBlockParam {
                 media_type: media_type.into(),
                 data: data.into(),
             },
+            cache_control: None,
         }
     }
     
@@ -333,6 +1326,7 @@ impl ContentBlockParam {
             source: ImageSource::Url {
                 url: url.into(),
             },
+            cache_control: None,
         }
     }
 
@@ -351,6 +1345,7 @@ impl ContentBlockParam {
                 media_type: file.mime_type.to_string(),
                 data: base64_data,
             },
+            cache_control: None,
         })
     }
 
@@ -358,6 +1353,142 @@ impl ContentBlockParam {
     pub async fn from_file(file: File) -> Result<Self, FileError> {
         Self::image_file(file).await
     }
+
+    /// Create an image block from an uploaded file ID
+    pub fn image_file_id(file_id: impl Into<String>) -> Self {
+        Self::Image {
+            source: ImageSource::File { file_id: file_id.into() },
+            cache_control: None,
+        }
+    }
+
+    /// Create a document block from an uploaded file ID
+    pub fn document_file_id(file_id: impl Into<String>) -> Self {
+        Self::document(DocumentSource::File { file_id: file_id.into() })
+    }
+
+    /// Create a block referencing an uploaded file, chosen by its MIME type
+    ///
+    /// Images become image blocks; PDF and plain text files become document
+    /// blocks titled with the filename.
+    pub fn from_file_object(file: &FileObject) -> Result<Self, FileError> {
+        let mime_type = file.content_type.split(';').next().unwrap_or_default().trim();
+        if mime_type.starts_with("image/") {
+            Ok(Self::image_file_id(&file.id))
+        } else if mime_type == "application/pdf" || mime_type == "text/plain" {
+            Ok(Self::document_file_id(&file.id).title(&file.filename))
+        } else {
+            Err(FileError::InvalidMimeType {
+                mime_type: file.content_type.clone(),
+                allowed: vec!["image/*".to_string(), "application/pdf".to_string(), "text/plain".to_string()],
+            })
+        }
+    }
+
+    /// Create a tool result block with text or block content
+    pub fn tool_result(tool_use_id: impl Into<String>, content: impl Into<MessageContent>) -> Self {
+        Self::ToolResult {
+            tool_use_id: tool_use_id.into(),
+            content: Some(content.into()),
+            is_error: None,
+            cache_control: None,
+        }
+    }
+
+    /// Create a document block from a source
+    pub fn document(source: DocumentSource) -> Self {
+        Self::Document {
+            source,
+            title: None,
+            context: None,
+            citations: None,
+            cache_control: None,
+        }
+    }
+
+    /// Create a document block from base64-encoded PDF data
+    pub fn document_pdf_base64(data: impl Into<String>) -> Self {
+        Self::document(DocumentSource::Base64 {
+            media_type: "application/pdf".to_string(),
+            data: data.into(),
+        })
+    }
+
+    /// Create a document block from a PDF URL
+    pub fn document_pdf_url(url: impl Into<String>) -> Self {
+        Self::document(DocumentSource::Url { url: url.into() })
+    }
+
+    /// Create a document block from plain text
+    pub fn document_text(text: impl Into<String>) -> Self {
+        Self::document(DocumentSource::Text {
+            media_type: "text/plain".to_string(),
+            data: text.into(),
+        })
+    }
+
+    /// Create a document block from custom content blocks
+    pub fn document_content(content: impl Into<MessageContent>) -> Self {
+        Self::document(DocumentSource::Content { content: content.into() })
+    }
+
+    /// Create a document block from a PDF or text File
+    pub async fn document_file(file: File) -> Result<Self, FileError> {
+        if file.mime_type == mime::APPLICATION_PDF {
+            let base64_data = file.to_base64().await?;
+            Ok(Self::document_pdf_base64(base64_data).title(file.name))
+        } else if file.is_text() {
+            let bytes = file.to_bytes().await?;
+            let text = String::from_utf8(bytes.to_vec()).map_err(|_| FileError::InvalidData)?;
+            Ok(Self::document_text(text).title(file.name))
+        } else {
+            Err(FileError::InvalidMimeType {
+                mime_type: file.mime_type.to_string(),
+                allowed: vec!["application/pdf".to_string(), "text/*".to_string()],
+            })
+        }
+    }
+
+    /// Set the title of a document block; other blocks are returned unchanged
+    pub fn title(mut self, value: impl Into<String>) -> Self {
+        if let Self::Document { title, .. } = &mut self {
+            *title = Some(value.into());
+        }
+        self
+    }
+
+    /// Set context about a document block that is not cited from
+    pub fn context(mut self, value: impl Into<String>) -> Self {
+        if let Self::Document { context, .. } = &mut self {
+            *context = Some(value.into());
+        }
+        self
+    }
+
+    /// Allow the model to cite a document block
+    pub fn enable_citations(mut self) -> Self {
+        if let Self::Document { citations, .. } = &mut self {
+            *citations = Some(CitationsConfig { enabled: true });
+        }
+        self
+    }
+
+    /// Mark this block as a cache breakpoint
+    ///
+    /// Thinking and unknown blocks cannot be cached directly and are returned unchanged.
+    pub fn cache_control(mut self, cache_control: CacheControl) -> Self {
+        match &mut self {
+            Self::Text { cache_control: slot, .. }
+            | Self::Image { cache_control: slot, .. }
+            | Self::ToolUse { cache_control: slot, .. }
+            | Self::ToolResult { cache_control: slot, .. }
+            | Self::Document { cache_control: slot, .. }
+            | Self::ServerToolUse { cache_control: slot, .. }
+            | Self::WebSearchToolResult { cache_control: slot, .. } => *slot = Some(cache_control),
+            Self::Thinking { .. } | Self::RedactedThinking { .. } | Self::Unknown(_) => {}
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -376,7 +1507,7 @@ mod tests {
         assert_eq!(params.max_tokens, 1024);
         assert_eq!(params.messages.len(), 1);
         assert_eq!(params.messages[0].role, Role::User);
-        assert_eq!(params.system, Some("You are a helpful assistant.".to_string()));
+        assert_eq!(params.system, Some(SystemPrompt::from("You are a helpful assistant.")));
         assert_eq!(params.temperature, Some(0.7));
     }
 
@@ -384,13 +1515,13 @@ mod tests {
     fn test_content_block_creation() {
         let text_block = ContentBlockParam::text("Hello world");
         match text_block {
-            ContentBlockParam::Text { text } => assert_eq!(text, "Hello world"),
+            ContentBlockParam::Text { text, .. } => assert_eq!(text, "Hello world"),
             _ => panic!("Expected text block"),
         }
 
         let image_block = ContentBlockParam::image_base64("image/jpeg", "base64data");
         match image_block {
-            ContentBlockParam::Image { source } => match source {
+            ContentBlockParam::Image { source, .. } => match source {
                 ImageSource::Base64 { media_type, data } => {
                     assert_eq!(media_type, "image/jpeg");
                     assert_eq!(data, "base64data");
@@ -401,6 +1532,541 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_thinking_blocks_round_trip() {
+        let json = serde_json::json!([
+            {"type": "thinking", "thinking": "Reasoning...", "signature": "sig_123"},
+            {"type": "redacted_thinking", "data": "encrypted"},
+        ]);
+        let blocks: Vec<ContentBlock> = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(blocks[0], ContentBlock::Thinking {
+            thinking: "Reasoning...".to_string(),
+            signature: "sig_123".to_string(),
+        });
+
+        let params: Vec<ContentBlockParam> = blocks.iter().map(ContentBlockParam::from).collect();
+        assert_eq!(serde_json::to_value(&params).unwrap(), json);
+    }
+
+    #[test]
+    fn test_message_accessors() {
+        let message: Message = serde_json::from_value(serde_json::json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "content": [
+                {"type": "thinking", "thinking": "First, ", "signature": "sig_1"},
+                {"type": "thinking", "thinking": "then.", "signature": "sig_2"},
+                {"type": "text", "text": "Checking "},
+                {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"city": "Oslo"}},
+                {"type": "text", "text": "both."},
+                {"type": "tool_use", "id": "toolu_2", "name": "get_time", "input": {}},
+            ],
+            "model": "claude-3-5-sonnet-latest",
+            "stop_reason": "tool_use",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 1, "output_tokens": 2},
+        }))
+        .unwrap();
+
+        assert_eq!(message.text(), "Checking both.");
+        assert_eq!(message.thinking().as_deref(), Some("First, then."));
+        let tool_uses = message.tool_uses();
+        assert_eq!(tool_uses.len(), 2);
+        assert_eq!(tool_uses[0].name, "get_weather");
+        assert_eq!(tool_uses[0].input, serde_json::json!({"city": "Oslo"}));
+        assert_eq!(tool_uses[1].id, "toolu_2");
+
+        let content = serde_json::to_value(&message.content).unwrap();
+        let param = MessageParam::from(message);
+        assert_eq!(param.role, Role::Assistant);
+        assert_eq!(serde_json::to_value(&param.content).unwrap(), content);
+    }
+
+    #[test]
+    fn test_param_to_block_conversion() {
+        let block = ContentBlock::from(ContentBlockParam::text("Hi").cache_control(CacheControl::ephemeral()));
+        assert_eq!(block, ContentBlock::Text { text: "Hi".to_string(), citations: None });
+
+        let result = ContentBlockParam::ToolResult {
+            tool_use_id: "toolu_1".to_string(),
+            content: Some(MessageContent::Text("done".to_string())),
+            is_error: None,
+            cache_control: None,
+        };
+        assert_eq!(
+            ContentBlock::from(result),
+            ContentBlock::ToolResult { tool_use_id: "toolu_1".to_string(), content: Some("done".to_string()), is_error: None }
+        );
+
+        // Request-only blocks survive a round trip as raw JSON
+        let document = ContentBlockParam::document_text("Some notes");
+        let raw = serde_json::to_value(&document).unwrap();
+        let block = ContentBlock::from(document);
+        assert_eq!(block, ContentBlock::Unknown(raw.clone()));
+        assert_eq!(serde_json::to_value(ContentBlockParam::from(block)).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_thinking_config_serialization() {
+        let params = MessageCreateBuilder::new("claude-sonnet-4-20250514", 2048)
+            .user("Hello")
+            .enable_thinking(1024)
+            .build();
+        let value = serde_json::to_value(&params).unwrap();
+        assert_eq!(value["thinking"], serde_json::json!({"type": "enabled", "budget_tokens": 1024}));
+
+        let disabled = serde_json::to_value(ThinkingConfig::disabled()).unwrap();
+        assert_eq!(disabled, serde_json::json!({"type": "disabled"}));
+    }
+
+    #[test]
+    fn test_cache_breakpoints() {
+        let tool = crate::types::Tool::new("lookup", "Look something up").build();
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .system_cached("You are a helpful assistant.")
+            .tools(vec![tool])
+            .cache_tools()
+            .user("Summarize the document")
+            .cache_last_message()
+            .build();
+
+        let value = serde_json::to_value(&params).unwrap();
+        let ephemeral = serde_json::json!({"type": "ephemeral"});
+        assert_eq!(value["system"][0]["text"], "You are a helpful assistant.");
+        assert_eq!(value["system"][0]["cache_control"], ephemeral);
+        assert_eq!(value["tools"][0]["cache_control"], ephemeral);
+        assert_eq!(value["messages"][0]["content"], serde_json::json!([
+            {"type": "text", "text": "Summarize the document", "cache_control": {"type": "ephemeral"}}
+        ]));
+    }
+
+    #[test]
+    fn test_request_parameters() {
+        let mut legacy = std::collections::HashMap::new();
+        legacy.insert("user_id".to_string(), "user-123".to_string());
+        assert_eq!(Metadata::from(legacy), Metadata::user_id("user-123"));
+
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .user("Hello")
+            .user_id("user-123")
+            .service_tier(ServiceTierPreference::StandardOnly)
+            .container("container_abc")
+            .mcp_server(
+                McpServer::url("docs", "https://mcp.example.com/sse")
+                    .authorization_token("token")
+                    .allowed_tools(["search"]),
+            )
+            .context_management(ContextManagement::new(ContextEdit::ClearToolUses {
+                trigger: Some(ContextThreshold::InputTokens { value: 50_000 }),
+                keep: Some(ContextThreshold::ToolUses { value: 3 }),
+                clear_at_least: None,
+                exclude_tools: Some(vec!["web_search".to_string()]),
+                clear_tool_inputs: None,
+            }))
+            .build();
+
+        let value = serde_json::to_value(&params).unwrap();
+        assert_eq!(value["metadata"], serde_json::json!({"user_id": "user-123"}));
+        assert_eq!(value["service_tier"], "standard_only");
+        assert_eq!(value["container"], "container_abc");
+        assert_eq!(value["mcp_servers"], serde_json::json!([{
+            "type": "url",
+            "url": "https://mcp.example.com/sse",
+            "name": "docs",
+            "authorization_token": "token",
+            "tool_configuration": {"allowed_tools": ["search"]},
+        }]));
+        assert_eq!(value["context_management"], serde_json::json!({"edits": [{
+            "type": "clear_tool_uses_20250919",
+            "trigger": {"type": "input_tokens", "value": 50000},
+            "keep": {"type": "tool_uses", "value": 3},
+            "exclude_tools": ["web_search"],
+        }]}));
+        assert_eq!(params.required_betas(), vec![MCP_CLIENT_BETA, CONTEXT_MANAGEMENT_BETA]);
+
+        let plain = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024).user("Hi").build();
+        let value = serde_json::to_value(&plain).unwrap();
+        assert!(value.get("service_tier").is_none());
+        assert!(value.get("mcp_servers").is_none());
+        assert!(plain.required_betas().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_blocks_and_stop_reasons() {
+        let json = serde_json::json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "content": [
+                {"type": "text", "text": "Hello"},
+                {"type": "hologram", "frames": [1, 2, 3]},
+            ],
+            "model": "claude-3-5-sonnet-latest",
+            "stop_reason": "daydreaming",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 1, "output_tokens": 2},
+        });
+        let message: Message = serde_json::from_value(json).unwrap();
+        assert_eq!(message.content[0], ContentBlock::Text { text: "Hello".to_string(), citations: None });
+        assert_eq!(message.stop_reason, Some(StopReason::Other("daydreaming".to_string())));
+
+        let raw = serde_json::json!({"type": "hologram", "frames": [1, 2, 3]});
+        assert_eq!(message.content[1], ContentBlock::Unknown(raw.clone()));
+        // Unknown blocks are sent back unchanged in history
+        assert_eq!(serde_json::to_value(ContentBlockParam::from(&message.content[1])).unwrap(), raw);
+
+        let known: StopReason = serde_json::from_value(serde_json::json!("end_turn")).unwrap();
+        assert_eq!(known, StopReason::EndTurn);
+        for (raw, reason) in [
+            ("pause_turn", StopReason::PauseTurn),
+            ("refusal", StopReason::Refusal),
+            ("model_context_window_exceeded", StopReason::ModelContextWindowExceeded),
+        ] {
+            assert_eq!(serde_json::from_value::<StopReason>(serde_json::json!(raw)).unwrap(), reason);
+        }
+        assert_eq!(serde_json::to_value(StopReason::Other("new".to_string())).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_server_tools_and_results() {
+        let tool = crate::types::Tool::new("lookup", "Look something up").build();
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .tools(vec![tool])
+            .tool(crate::types::ServerTool::web_search_with_params(
+                crate::types::WebSearchParameters::new().max_uses(3).blocked_domains(["example.org"]),
+            ))
+            .cache_tools()
+            .user("What's new in Rust?")
+            .build();
+
+        let value = serde_json::to_value(&params).unwrap();
+        assert_eq!(value["tools"][0]["name"], "lookup");
+        assert_eq!(value["tools"][1], serde_json::json!({
+            "type": "web_search_20250305",
+            "name": "web_search",
+            "max_uses": 3,
+            "blocked_domains": ["example.org"],
+            "cache_control": {"type": "ephemeral"},
+        }));
+
+        let json = serde_json::json!([
+            {"type": "server_tool_use", "id": "srvtoolu_1", "name": "web_search", "input": {"query": "rust"}},
+            {"type": "web_search_tool_result", "tool_use_id": "srvtoolu_1", "content": [
+                {"type": "web_search_result", "url": "https://www.rust-lang.org", "title": "Rust",
+                 "encrypted_content": "abc", "page_age": "1 day ago"}
+            ]},
+            {"type": "web_search_tool_result", "tool_use_id": "srvtoolu_2",
+             "content": {"type": "web_search_tool_result_error", "error_code": "too_many_requests"}},
+        ]);
+        let blocks: Vec<ContentBlock> = serde_json::from_value(json.clone()).unwrap();
+        match &blocks[1] {
+            ContentBlock::WebSearchToolResult { content, .. } => {
+                assert_eq!(content.results()[0].title, "Rust");
+            }
+            other => panic!("unexpected block: {:?}", other),
+        }
+
+        // Blocks are replayed unchanged on the next turn
+        let params: Vec<ContentBlockParam> = blocks.iter().map(ContentBlockParam::from).collect();
+        assert_eq!(serde_json::to_value(&params).unwrap(), json);
+    }
+
+    #[test]
+    fn test_cache_control_ttl_and_system_text() {
+        let block = ContentBlockParam::text("Long context")
+            .cache_control(CacheControl::ephemeral_with_ttl(crate::types::CacheTtl::OneHour));
+        assert_eq!(
+            serde_json::to_value(&block).unwrap(),
+            serde_json::json!({"type": "text", "text": "Long context", "cache_control": {"type": "ephemeral", "ttl": "1h"}})
+        );
+
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .system("Plain prompt")
+            .user("Hi")
+            .build();
+        assert_eq!(serde_json::to_value(&params).unwrap()["system"], "Plain prompt");
+
+        let parsed: SystemPrompt = serde_json::from_value(serde_json::json!([
+            {"type": "text", "text": "First"},
+            {"type": "text", "text": "Second", "cache_control": {"type": "ephemeral"}},
+        ])).unwrap();
+        assert_eq!(parsed.to_text(), "First\nSecond");
+    }
+
+    #[test]
+    fn test_document_blocks() {
+        let pdf = ContentBlockParam::document_pdf_url("https://example.com/report.pdf")
+            .title("Annual report")
+            .context("Published in 2024")
+            .enable_citations();
+        assert_eq!(serde_json::to_value(&pdf).unwrap(), serde_json::json!({
+            "type": "document",
+            "source": {"type": "url", "url": "https://example.com/report.pdf"},
+            "title": "Annual report",
+            "context": "Published in 2024",
+            "citations": {"enabled": true},
+        }));
+
+        let text = ContentBlockParam::document_text("The grass is green.");
+        assert_eq!(serde_json::to_value(&text).unwrap(), serde_json::json!({
+            "type": "document",
+            "source": {"type": "text", "media_type": "text/plain", "data": "The grass is green."},
+        }));
+
+        let custom = ContentBlockParam::document_content(vec![
+            ContentBlockParam::text("First chunk"),
+            ContentBlockParam::text("Second chunk"),
+        ]);
+        let value = serde_json::to_value(&custom).unwrap();
+        assert_eq!(value["source"]["type"], "content");
+        assert_eq!(value["source"]["content"][1]["text"], "Second chunk");
+
+        let parsed: ContentBlockParam = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, custom);
+    }
+
+    #[tokio::test]
+    async fn test_document_file() {
+        let pdf = File::from_bytes("report.pdf", b"%PDF-1.4".to_vec(), Some(mime::APPLICATION_PDF)).unwrap();
+        match ContentBlockParam::document_file(pdf).await.unwrap() {
+            ContentBlockParam::Document { source: DocumentSource::Base64 { media_type, .. }, title, .. } => {
+                assert_eq!(media_type, "application/pdf");
+                assert_eq!(title.as_deref(), Some("report.pdf"));
+            }
+            other => panic!("Expected PDF document, got {:?}", other),
+        }
+
+        let notes = File::from_bytes("notes.txt", b"hello".to_vec(), Some(mime::TEXT_PLAIN)).unwrap();
+        match ContentBlockParam::document_file(notes).await.unwrap() {
+            ContentBlockParam::Document { source: DocumentSource::Text { data, .. }, .. } => assert_eq!(data, "hello"),
+            other => panic!("Expected text document, got {:?}", other),
+        }
+
+        let image = File::from_bytes("photo.png", b"png".to_vec(), Some(mime::IMAGE_PNG)).unwrap();
+        assert!(ContentBlockParam::document_file(image).await.is_err());
+    }
+
+    #[test]
+    fn test_text_block_citations() {
+        let json = serde_json::json!({
+            "type": "text",
+            "text": "the grass is green",
+            "citations": [{
+                "type": "char_location",
+                "cited_text": "The grass is green.",
+                "document_index": 0,
+                "document_title": "Facts",
+                "start_char_index": 0,
+                "end_char_index": 19,
+            }],
+        });
+        let block: ContentBlock = serde_json::from_value(json.clone()).unwrap();
+        match &block {
+            ContentBlock::Text { citations: Some(citations), .. } => {
+                assert_eq!(citations[0].cited_text(), "The grass is green.");
+                assert_eq!(citations[0].document_index(), Some(0));
+            }
+            other => panic!("Expected cited text, got {:?}", other),
+        }
+        assert_eq!(serde_json::to_value(&block).unwrap(), json);
+
+        let plain: ContentBlock = serde_json::from_value(serde_json::json!({"type": "text", "text": "Hi"})).unwrap();
+        assert_eq!(plain, ContentBlock::Text { text: "Hi".to_string(), citations: None });
+    }
+
+    #[test]
+    fn test_resolve_citations() {
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .user(vec![
+                ContentBlockParam::document_text("Héllo world. The sky is blue.").enable_citations(),
+                ContentBlockParam::document_pdf_url("https://example.com/a.pdf"),
+            ])
+            .user(vec![
+                ContentBlockParam::document_content(vec![
+                    ContentBlockParam::text("Chunk one"),
+                    ContentBlockParam::text("Chunk two"),
+                ]),
+                ContentBlockParam::text("Summarize"),
+            ])
+            .build();
+        assert_eq!(params.documents().len(), 3);
+
+        let char_citation = TextCitation::CharLocation {
+            cited_text: "The sky is blue.".to_string(),
+            document_index: 0,
+            document_title: None,
+            start_char_index: 13,
+            end_char_index: 29,
+        };
+        assert_eq!(char_citation.resolve(&params), Some(CitedSource::Text("The sky is blue.")));
+
+        let page_citation = TextCitation::PageLocation {
+            cited_text: "...".to_string(),
+            document_index: 1,
+            document_title: None,
+            start_page_number: 2,
+            end_page_number: 3,
+        };
+        match page_citation.resolve(&params) {
+            Some(CitedSource::Pages { start_page, end_page, .. }) => assert_eq!((start_page, end_page), (2, 3)),
+            other => panic!("Expected page span, got {:?}", other),
+        }
+
+        let block_citation = TextCitation::ContentBlockLocation {
+            cited_text: "Chunk two".to_string(),
+            document_index: 2,
+            document_title: None,
+            start_block_index: 1,
+            end_block_index: 2,
+        };
+        assert_eq!(block_citation.resolve(&params), Some(CitedSource::Blocks(&[ContentBlockParam::text("Chunk two")])));
+
+        let out_of_range = TextCitation::CharLocation {
+            cited_text: String::new(),
+            document_index: 5,
+            document_title: None,
+            start_char_index: 0,
+            end_char_index: 1,
+        };
+        assert_eq!(out_of_range.resolve(&params), None);
+    }
+
+    #[test]
+    fn test_documents_include_tool_results() {
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .user(vec![ContentBlockParam::document_text("First document")])
+            .assistant(vec![ContentBlockParam::ToolUse {
+                id: "toolu_1".to_string(),
+                name: "fetch".to_string(),
+                input: serde_json::json!({}),
+                cache_control: None,
+            }])
+            .user(vec![ContentBlockParam::tool_result(
+                "toolu_1",
+                vec![ContentBlockParam::text("Fetched:"), ContentBlockParam::document_text("Fetched document")],
+            )])
+            .build();
+
+        let documents = params.documents();
+        assert_eq!(documents.len(), 2);
+        let citation = TextCitation::CharLocation {
+            cited_text: "Fetched".to_string(),
+            document_index: 1,
+            document_title: None,
+            start_char_index: 0,
+            end_char_index: 7,
+        };
+        assert_eq!(citation.resolve(&params), Some(CitedSource::Text("Fetched")));
+    }
+
+    #[test]
+    fn test_file_object_blocks() {
+        let image = crate::testing::fixtures::file_object("chart.png", "image/png", 1024);
+        let block = ContentBlockParam::from_file_object(&image).unwrap();
+        assert_eq!(
+            serde_json::to_value(&block).unwrap(),
+            serde_json::json!({"type": "image", "source": {"type": "file", "file_id": image.id}})
+        );
+
+        let pdf = crate::testing::fixtures::file_object("report.pdf", "application/pdf", 2048);
+        let block = ContentBlockParam::from_file_object(&pdf).unwrap();
+        assert_eq!(
+            serde_json::to_value(&block).unwrap(),
+            serde_json::json!({"type": "document", "source": {"type": "file", "file_id": pdf.id}, "title": "report.pdf"})
+        );
+
+        let notes = crate::testing::fixtures::file_object("notes.txt", "text/plain; charset=utf-8", 10);
+        assert!(matches!(ContentBlockParam::from_file_object(&notes).unwrap(), ContentBlockParam::Document { .. }));
+
+        let archive = crate::testing::fixtures::file_object("data.zip", "application/zip", 10);
+        assert!(ContentBlockParam::from_file_object(&archive).is_err());
+
+        // Only plain text is accepted as a document source
+        let page = crate::testing::fixtures::file_object("page.html", "text/html", 10);
+        match ContentBlockParam::from_file_object(&page) {
+            Err(FileError::InvalidMimeType { mime_type, allowed }) => {
+                assert_eq!(mime_type, "text/html");
+                assert!(allowed.contains(&"text/plain".to_string()));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .user(vec![block, ContentBlockParam::text("Summarize")])
+            .build();
+        assert_eq!(params.required_betas(), vec![crate::types::files_api::FILES_API_BETA]);
+
+        let plain = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024).user("Hi").build();
+        assert!(plain.required_betas().is_empty());
+    }
+
+    #[test]
+    fn test_files_beta_detected_inside_tool_results() {
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .user("Render the chart")
+            .assistant(vec![ContentBlockParam::ToolUse {
+                id: "toolu_1".to_string(),
+                name: "render".to_string(),
+                input: serde_json::json!({}),
+                cache_control: None,
+            }])
+            .user(vec![ContentBlockParam::tool_result(
+                "toolu_1",
+                vec![ContentBlockParam::image_file_id("file_chart")],
+            )])
+            .build();
+        assert_eq!(params.required_betas(), vec![crate::types::files_api::FILES_API_BETA]);
+        assert_eq!(
+            MessageCountTokensParams::from(&params).required_betas(),
+            vec![crate::types::files_api::FILES_API_BETA]
+        );
+
+        let text_result = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .user(vec![ContentBlockParam::tool_result("toolu_1", "done")])
+            .build();
+        assert!(text_result.required_betas().is_empty());
+    }
+
+    #[test]
+    fn test_count_tokens_params_drop_generation_fields() {
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .system("Be brief")
+            .user("Hello")
+            .temperature(0.5)
+            .enable_thinking(1024)
+            .stream(true)
+            .build();
+
+        let value = serde_json::to_value(MessageCountTokensParams::from(&params)).unwrap();
+        assert_eq!(value["model"], "claude-3-5-sonnet-latest");
+        assert_eq!(value["system"], "Be brief");
+        assert_eq!(value["thinking"]["budget_tokens"], 1024);
+        for field in ["max_tokens", "temperature", "stream"] {
+            assert!(value.get(field).is_none(), "{} should not be sent", field);
+        }
+    }
+
+    #[test]
+    fn test_count_tokens_params_keep_beta_fields() {
+        let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+            .user("Hello")
+            .mcp_server(McpServer::url("docs", "https://mcp.example.com/sse"))
+            .context_management(ContextManagement::new(ContextEdit::ClearToolUses {
+                trigger: None,
+                keep: None,
+                clear_at_least: None,
+                exclude_tools: None,
+                clear_tool_inputs: None,
+            }))
+            .build();
+
+        let count_params = MessageCountTokensParams::from(&params);
+        assert_eq!(count_params.mcp_servers, params.mcp_servers);
+        assert_eq!(count_params.context_management, params.context_management);
+        assert_eq!(count_params.required_betas(), vec![MCP_CLIENT_BETA, CONTEXT_MANAGEMENT_BETA]);
+    }
+
     #[test]
     fn test_message_content_from_string() {
         let content: MessageContent = "Hello".into();
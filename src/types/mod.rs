@@ -5,22 +5,28 @@ pub mod models;
 pub mod models_api;
 pub mod streaming;
 pub mod tools;
+pub mod schema;
+pub mod validation;
 pub mod batches;
 pub mod files_api;
 
 // Re-exports for convenience
 pub use errors::{AnthropicError, Result};
-pub use shared::{RequestId, Usage, ServerToolUsage, HasRequestId};
+pub use shared::{RequestId, Usage, ServerToolUsage, ServiceTier, HasRequestId, CacheControl, CacheTtl};
 
 // Message types
 pub use messages::{
     Message, Role, ContentBlock, ImageSource, StopReason,
     MessageCreateParams, MessageParam, MessageContent, ContentBlockParam,
-    MessageCreateBuilder,
+    MessageCreateBuilder, ThinkingConfig, SystemPrompt, SystemBlock,
+    DocumentSource, CitationsConfig, CitedSource, MessageCountTokensParams, MessageTokensCount,
+    Metadata, ServiceTierPreference, McpServer, McpToolConfiguration,
+    ContextManagement, ContextEdit, ContextThreshold, MCP_CLIENT_BETA, CONTEXT_MANAGEMENT_BETA,
+    ContinuationLimits,
 };
 
 // Model types
-pub use models::Model;
+pub use models::{Model, max_output_tokens};
 
 // Streaming types
 pub use streaming::{
@@ -34,8 +40,12 @@ pub use streaming::{
 pub use tools::{
     Tool, ToolBuilder, ToolChoice, ToolUse, ToolResult, ToolResultContent,
     ToolResultBlock, ToolInputSchema, ToolValidationError,
-    ServerTool, WebSearchParameters, ImageSource as ToolImageSource,
+    ServerTool, WebSearchParameters, UserLocation, ToolUnion,
+    WebSearchResult, WebSearchToolResultError, WebSearchErrorCode, WebSearchToolResultContent,
+    ImageSource as ToolImageSource,
 };
+pub use schema::SchemaViolation;
+pub use validation::RequestValidationError;
 
 // Batch types
 pub use batches::{
@@ -47,7 +57,7 @@ pub use batches::{
 // Files API types
 pub use files_api::{
     FileObject, FilePurpose, FileStatus, FileUploadParams, FileListParams, FileList,
-    FileOrder, UploadProgress, StorageInfo, FileDownload,
+    FileOrder, UploadProgress, StorageInfo, FileDownload, FILES_API_BETA,
 };
 
 // Models API types
@@ -82,6 +82,44 @@ impl Model {
             _ => true,
         }
     }
+    
+    /// Maximum `max_tokens` the model accepts, or `None` if this SDK doesn't know the model
+    pub fn max_output_tokens(&self) -> Option<u32> {
+        max_output_tokens(self.as_str())
+    }
+}
+
+/// Output token limits by model ID prefix; more specific prefixes come first
+const OUTPUT_TOKEN_LIMITS: &[(&str, u32)] = &[
+    ("claude-opus-4-5", 64_000),
+    ("claude-sonnet-4-5", 64_000),
+    ("claude-haiku-4-5", 64_000),
+    ("claude-opus-4-1", 32_000),
+    ("claude-opus-4", 32_000),
+    ("claude-sonnet-4", 64_000),
+    // 128K needs the output-128k beta header, which requests don't send by default
+    ("claude-3-7-sonnet", 64_000),
+    ("claude-3-5-sonnet", 8_192),
+    ("claude-3-5-haiku", 8_192),
+    ("claude-3-opus", 4_096),
+    ("claude-3-sonnet", 4_096),
+    ("claude-3-haiku", 4_096),
+    ("claude-2.1", 4_096),
+    ("claude-2.0", 4_096),
+];
+
+/// Maximum `max_tokens` for a model ID, if the model is known
+///
+/// Matches a known model name followed by nothing, `-latest` or a date
+/// snapshot, so newer models that share a prefix aren't given a stale limit.
+pub fn max_output_tokens(model: &str) -> Option<u32> {
+    OUTPUT_TOKEN_LIMITS.iter().find_map(|(prefix, limit)| {
+        let rest = model.strip_prefix(prefix)?;
+        let is_snapshot = rest
+            .strip_prefix('-')
+            .is_some_and(|date| date.len() == 8 && date.bytes().all(|byte| byte.is_ascii_digit()));
+        (rest.is_empty() || rest == "-latest" || is_snapshot).then_some(*limit)
+    })
 }
 
 impl std::fmt::Display for Model {
@@ -0,0 +1,535 @@
+//! JSON Schema validation for tool inputs.
+//!
+//! Implements the parts of draft 2020-12 that tool input schemas use: types,
+//! `enum`/`const`, object and array keywords, string and number bounds,
+//! `pattern`, the `allOf`/`anyOf`/`oneOf`/`not` and `if`/`then`/`else`
+//! combinators, and local `$ref`s into `$defs` or `definitions`. Annotation
+//! keywords such as `format` and `description` are ignored. Every violation
+//! carries the JSON pointer of the offending value.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Limit on nested `$ref` resolution, so recursive schemas can't loop forever.
+const MAX_REF_DEPTH: usize = 64;
+
+/// A single place where a value doesn't match its schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value; empty for the input itself.
+    pub path: String,
+
+    /// The schema keyword that failed, e.g. `type` or `required`.
+    pub keyword: String,
+
+    /// What went wrong.
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() { "(root)" } else { &self.path };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+/// Validate `instance` against `schema`, returning every violation found.
+///
+/// # Examples
+/// ```rust
+/// use anthropic_sdk::types::schema::validate;
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {"tags": {"type": "array", "items": {"type": "string"}}},
+/// });
+///
+/// let violations = validate(&schema, &json!({"tags": ["a", 2]}));
+/// assert_eq!(violations[0].path, "/tags/1");
+/// assert_eq!(violations[0].keyword, "type");
+/// ```
+pub fn validate(schema: &Value, instance: &Value) -> Vec<SchemaViolation> {
+    let mut validator = Validator { root: schema, violations: Vec::new(), depth: 0 };
+    validator.check(schema, instance, "");
+    validator.violations
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    violations: Vec<SchemaViolation>,
+    depth: usize,
+}
+
+impl<'a> Validator<'a> {
+    fn fail(&mut self, path: &str, keyword: &str, message: impl Into<String>) {
+        self.violations.push(SchemaViolation {
+            path: path.to_string(),
+            keyword: keyword.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Whether `instance` matches `schema`, without recording violations.
+    fn matches(&self, schema: &'a Value, instance: &Value) -> bool {
+        let mut validator = Validator { root: self.root, violations: Vec::new(), depth: self.depth };
+        validator.check(schema, instance, "");
+        validator.violations.is_empty()
+    }
+
+    fn check(&mut self, schema: &'a Value, instance: &Value, path: &str) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => return self.fail(path, "false", "no value is allowed here"),
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            self.check_ref(reference, instance, path);
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let expected: Vec<&str> = match expected {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !expected.is_empty() && !expected.iter().any(|name| has_type(instance, name)) {
+                self.fail(path, "type", format!("expected {}, got {}", expected.join(" or "), type_name(instance)));
+                // Keywords for other types would only add noise
+                return;
+            }
+        }
+
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            if !options.iter().any(|option| json_equal(option, instance)) {
+                let options: Vec<String> = options.iter().map(Value::to_string).collect();
+                self.fail(path, "enum", format!("expected one of {}, got {}", options.join(", "), instance));
+            }
+        }
+
+        if let Some(constant) = schema.get("const") {
+            if !json_equal(constant, instance) {
+                self.fail(path, "const", format!("expected {}, got {}", constant, instance));
+            }
+        }
+
+        self.check_combinators(schema, instance, path);
+
+        match instance {
+            Value::Object(object) => self.check_object(schema, object, path),
+            Value::Array(items) => self.check_array(schema, items, path),
+            Value::String(string) => self.check_string(schema, string, path),
+            Value::Number(_) => self.check_number(schema, instance, path),
+            _ => {}
+        }
+    }
+
+    fn check_ref(&mut self, reference: &str, instance: &Value, path: &str) {
+        if self.depth >= MAX_REF_DEPTH {
+            return self.fail(path, "$ref", format!("too many nested references resolving {}", reference));
+        }
+        let Some(target) = resolve_ref(self.root, reference) else {
+            return self.fail(path, "$ref", format!("cannot resolve reference {}", reference));
+        };
+        self.depth += 1;
+        self.check(target, instance, path);
+        self.depth -= 1;
+    }
+
+    fn check_combinators(&mut self, schema: &'a Map<String, Value>, instance: &Value, path: &str) {
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            for subschema in schemas {
+                self.check(subschema, instance, path);
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+            if !schemas.iter().any(|subschema| self.matches(subschema, instance)) {
+                self.fail(path, "anyOf", "value does not match any of the allowed schemas");
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+            let matched = schemas.iter().filter(|subschema| self.matches(subschema, instance)).count();
+            if matched != 1 {
+                self.fail(path, "oneOf", format!("value must match exactly one schema, matched {}", matched));
+            }
+        }
+
+        if let Some(subschema) = schema.get("not") {
+            if self.matches(subschema, instance) {
+                self.fail(path, "not", "value matches a schema it must not match");
+            }
+        }
+
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.matches(condition, instance) { "then" } else { "else" };
+            if let Some(subschema) = schema.get(branch) {
+                self.check(subschema, instance, path);
+            }
+        }
+    }
+
+    fn check_object(&mut self, schema: &'a Map<String, Value>, object: &Map<String, Value>, path: &str) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    self.fail(&child_path(path, name), "required", "required property is missing");
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let patterns: Vec<(regex::Regex, &'a Value)> = schema
+            .get("patternProperties")
+            .and_then(Value::as_object)
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .filter_map(|(pattern, subschema)| Some((regex::Regex::new(pattern).ok()?, subschema)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let additional = schema.get("additionalProperties");
+
+        for (name, value) in object {
+            let value_path = child_path(path, name);
+            let mut evaluated = false;
+            if let Some(subschema) = properties.and_then(|properties| properties.get(name)) {
+                self.check(subschema, value, &value_path);
+                evaluated = true;
+            }
+            for (pattern, subschema) in &patterns {
+                if pattern.is_match(name) {
+                    self.check(subschema, value, &value_path);
+                    evaluated = true;
+                }
+            }
+            match additional {
+                Some(Value::Bool(false)) if !evaluated => {
+                    self.fail(&value_path, "additionalProperties", "property is not allowed");
+                }
+                Some(subschema) if !evaluated => self.check(subschema, value, &value_path),
+                _ => {}
+            }
+            if let Some(subschema) = schema.get("propertyNames") {
+                if !self.matches(subschema, &Value::String(name.clone())) {
+                    self.fail(&value_path, "propertyNames", format!("property name '{}' is not allowed", name));
+                }
+            }
+        }
+
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if (object.len() as u64) < min {
+                self.fail(path, "minProperties", format!("expected at least {} properties, got {}", min, object.len()));
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if object.len() as u64 > max {
+                self.fail(path, "maxProperties", format!("expected at most {} properties, got {}", max, object.len()));
+            }
+        }
+    }
+
+    fn check_array(&mut self, schema: &'a Map<String, Value>, items: &[Value], path: &str) {
+        // Array-form `items` is the pre-2020-12 spelling of `prefixItems`
+        let (prefix, rest) = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix)), rest) => (prefix.as_slice(), rest),
+            (None, Some(Value::Array(prefix))) => (prefix.as_slice(), schema.get("additionalItems")),
+            (_, rest) => (&[][..], rest),
+        };
+        for (index, item) in items.iter().enumerate() {
+            let item_path = child_path(path, &index.to_string());
+            match prefix.get(index) {
+                Some(subschema) => self.check(subschema, item, &item_path),
+                None => {
+                    if let Some(subschema) = rest {
+                        if subschema == &Value::Bool(false) {
+                            self.fail(&item_path, "items", format!("expected at most {} items", prefix.len()));
+                        } else {
+                            self.check(subschema, item, &item_path);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                self.fail(path, "minItems", format!("expected at least {} items, got {}", min, items.len()));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if items.len() as u64 > max {
+                self.fail(path, "maxItems", format!("expected at most {} items, got {}", max, items.len()));
+            }
+        }
+
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            for (index, item) in items.iter().enumerate() {
+                if items[..index].iter().any(|earlier| json_equal(earlier, item)) {
+                    self.fail(&child_path(path, &index.to_string()), "uniqueItems", "duplicate item");
+                }
+            }
+        }
+
+        if let Some(subschema) = schema.get("contains") {
+            let matched = items.iter().filter(|item| self.matches(subschema, item)).count() as u64;
+            let min = schema.get("minContains").and_then(Value::as_u64).unwrap_or(1);
+            if matched < min {
+                self.fail(path, "contains", format!("expected at least {} matching items, got {}", min, matched));
+            }
+            if let Some(max) = schema.get("maxContains").and_then(Value::as_u64) {
+                if matched > max {
+                    self.fail(path, "maxContains", format!("expected at most {} matching items, got {}", max, matched));
+                }
+            }
+        }
+    }
+
+    fn check_string(&mut self, schema: &Map<String, Value>, string: &str, path: &str) {
+        let length = string.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                self.fail(path, "minLength", format!("expected at least {} characters, got {}", min, length));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                self.fail(path, "maxLength", format!("expected at most {} characters, got {}", max, length));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            // Patterns the regex engine can't compile are treated as annotations
+            if let Ok(regex) = regex::Regex::new(pattern) {
+                if !regex.is_match(string) {
+                    self.fail(path, "pattern", format!("does not match pattern {}", pattern));
+                }
+            }
+        }
+    }
+
+    fn check_number(&mut self, schema: &Map<String, Value>, instance: &Value, path: &str) {
+        let Some(number) = instance.as_f64() else {
+            return;
+        };
+        let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+
+        if let Some(minimum) = bound("minimum") {
+            if number < minimum {
+                self.fail(path, "minimum", format!("expected at least {}, got {}", minimum, instance));
+            }
+        }
+        if let Some(maximum) = bound("maximum") {
+            if number > maximum {
+                self.fail(path, "maximum", format!("expected at most {}, got {}", maximum, instance));
+            }
+        }
+        if let Some(minimum) = bound("exclusiveMinimum") {
+            if number <= minimum {
+                self.fail(path, "exclusiveMinimum", format!("expected more than {}, got {}", minimum, instance));
+            }
+        }
+        if let Some(maximum) = bound("exclusiveMaximum") {
+            if number >= maximum {
+                self.fail(path, "exclusiveMaximum", format!("expected less than {}, got {}", maximum, instance));
+            }
+        }
+        if let Some(divisor) = bound("multipleOf").filter(|divisor| *divisor > 0.0) {
+            let quotient = number / divisor;
+            if (quotient - quotient.round()).abs() > 1e-9 {
+                self.fail(path, "multipleOf", format!("expected a multiple of {}, got {}", divisor, instance));
+            }
+        }
+    }
+}
+
+/// Resolve a local reference such as `#/$defs/Unit`.
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    if pointer.is_empty() {
+        return Some(root);
+    }
+    let pointer = percent_decode(pointer);
+    root.pointer(&pointer)
+}
+
+/// Decode the `%XX` escapes allowed in URI fragments.
+fn percent_decode(fragment: &str) -> String {
+    let bytes = fragment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = |byte: u8| (byte as char).to_digit(16);
+            if let (Some(high), Some(low)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                decoded.push((high * 16 + low) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Append a segment to a JSON pointer, escaping `~` and `/`.
+fn child_path(path: &str, segment: &str) -> String {
+    format!("{}/{}", path, segment.replace('~', "~0").replace('/', "~1"))
+}
+
+fn has_type(instance: &Value, name: &str) -> bool {
+    match name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64() || instance.is_u64() || instance.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Equality as JSON Schema defines it, where `1` and `1.0` are the same number.
+fn json_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a == b || a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_equal(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| json_equal(a, b)))
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(schema: &Value, instance: &Value) -> Vec<(String, String)> {
+        validate(schema, instance)
+            .into_iter()
+            .map(|violation| (violation.path, violation.keyword))
+            .collect()
+    }
+
+    fn violation(path: &str, keyword: &str) -> (String, String) {
+        (path.to_string(), keyword.to_string())
+    }
+
+    #[test]
+    fn test_nested_objects_and_arrays() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "object",
+                    "properties": {"city": {"type": "string"}, "zip": {"type": "string", "pattern": "^[0-9]{5}$"}},
+                    "required": ["city"],
+                    "additionalProperties": false,
+                },
+                "tags": {"type": "array", "items": {"type": "string", "minLength": 1}, "maxItems": 2, "uniqueItems": true},
+            },
+            "required": ["address"],
+        });
+
+        assert!(validate(&schema, &json!({"address": {"city": "Paris", "zip": "75001"}, "tags": ["a"]})).is_empty());
+        assert_eq!(paths(&schema, &json!({})), vec![violation("/address", "required")]);
+        assert_eq!(
+            paths(&schema, &json!({"address": {"zip": "7500", "country": "FR"}})),
+            vec![
+                violation("/address/city", "required"),
+                violation("/address/country", "additionalProperties"),
+                violation("/address/zip", "pattern"),
+            ]
+        );
+        assert_eq!(
+            paths(&schema, &json!({"address": {"city": "Paris"}, "tags": ["", "b", "b"]})),
+            vec![
+                violation("/tags/0", "minLength"),
+                violation("/tags", "maxItems"),
+                violation("/tags/2", "uniqueItems"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scalar_keywords() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "count": {"type": "integer", "minimum": 1, "exclusiveMaximum": 10, "multipleOf": 2},
+                "unit": {"enum": ["celsius", "fahrenheit"]},
+                "version": {"const": 1},
+                "ratio": {"type": ["number", "null"]},
+            },
+        });
+
+        assert!(validate(&schema, &json!({"count": 4, "unit": "celsius", "version": 1.0, "ratio": null})).is_empty());
+        assert_eq!(
+            paths(&schema, &json!({"count": 0, "unit": "kelvin", "version": 2, "ratio": "x"})),
+            vec![
+                violation("/count", "minimum"),
+                violation("/ratio", "type"),
+                violation("/unit", "enum"),
+                violation("/version", "const"),
+            ]
+        );
+        assert_eq!(paths(&schema, &json!({"count": 10})), vec![violation("/count", "exclusiveMaximum")]);
+        assert_eq!(paths(&schema, &json!({"count": 3})), vec![violation("/count", "multipleOf")]);
+        assert_eq!(paths(&schema, &json!({"count": 2.5})), vec![violation("/count", "type")]);
+    }
+
+    #[test]
+    fn test_combinators_and_refs() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "shape": {"oneOf": [{"$ref": "#/$defs/Circle"}, {"$ref": "#/$defs/Square"}]},
+                "id": {"anyOf": [{"type": "string"}, {"type": "integer"}]},
+                "name": {"not": {"const": "admin"}},
+            },
+            "$defs": {
+                "Circle": {"type": "object", "properties": {"radius": {"type": "number"}}, "required": ["radius"]},
+                "Square": {"type": "object", "properties": {"side": {"type": "number"}}, "required": ["side"]},
+            },
+        });
+
+        assert!(validate(&schema, &json!({"shape": {"radius": 1}, "id": 7, "name": "jo"})).is_empty());
+        assert_eq!(
+            paths(&schema, &json!({"shape": {"radius": 1, "side": 2}, "id": true, "name": "admin"})),
+            vec![violation("/id", "anyOf"), violation("/name", "not"), violation("/shape", "oneOf")]
+        );
+        assert_eq!(paths(&json!({"$ref": "#/$defs/Missing"}), &json!(1)), vec![violation("", "$ref")]);
+    }
+
+    #[test]
+    fn test_pointer_escaping_and_display() {
+        let schema = json!({"type": "object", "additionalProperties": {"type": "integer"}});
+        let violations = validate(&schema, &json!({"a/b~c": "x"}));
+        assert_eq!(violations[0].path, "/a~1b~0c");
+        assert_eq!(violations[0].to_string(), "/a~1b~0c: expected integer, got string");
+        assert_eq!(validate(&schema, &json!([]))[0].to_string(), "(root): expected object, got array");
+    }
+}
@@ -43,7 +43,20 @@ pub struct Usage {
     
     /// Service tier used for the request
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub service_tier: Option<String>,
+    pub service_tier: Option<ServiceTier>,
+}
+
+/// Capacity a request was served from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceTier {
+    Standard,
+    Priority,
+    Batch,
+    
+    /// A tier not known to this version of the SDK
+    #[serde(untagged)]
+    Other(String),
 }
 
 /// Server tool usage statistics
@@ -65,6 +78,62 @@ impl Usage {
             + self.cache_creation_input_tokens.unwrap_or(0)
             + self.cache_read_input_tokens.unwrap_or(0)
     }
+    
+    /// Add the usage of another request to this one, e.g. across continuations
+    pub fn accumulate(&mut self, other: &Usage) {
+        fn add(total: &mut Option<u32>, other: Option<u32>) {
+            if let Some(other) = other {
+                *total = Some(total.unwrap_or(0) + other);
+            }
+        }
+        
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        add(&mut self.cache_creation_input_tokens, other.cache_creation_input_tokens);
+        add(&mut self.cache_read_input_tokens, other.cache_read_input_tokens);
+        if let Some(other) = &other.server_tool_use {
+            self.server_tool_use
+                .get_or_insert(ServerToolUsage { web_search_requests: 0 })
+                .web_search_requests += other.web_search_requests;
+        }
+        if other.service_tier.is_some() {
+            self.service_tier = other.service_tier.clone();
+        }
+    }
+}
+
+/// Marks a prompt cache breakpoint on a block or tool
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CacheControl {
+    /// Cache the prompt prefix up to and including this block
+    Ephemeral {
+        /// Time to live; the API defaults to five minutes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ttl: Option<CacheTtl>,
+    },
+}
+
+impl CacheControl {
+    /// Ephemeral cache breakpoint with the default TTL
+    pub fn ephemeral() -> Self {
+        Self::Ephemeral { ttl: None }
+    }
+    
+    /// Ephemeral cache breakpoint with an explicit TTL
+    pub fn ephemeral_with_ttl(ttl: CacheTtl) -> Self {
+        Self::Ephemeral { ttl: Some(ttl) }
+    }
+}
+
+/// Lifetime of a prompt cache entry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CacheTtl {
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    
+    #[serde(rename = "1h")]
+    OneHour,
 }
 
 /// Base trait for responses that include request IDs
@@ -7,6 +7,9 @@
 use serde::{Deserialize, Serialize};
 use crate::types::{Message, ContentBlock, StopReason, ServerToolUsage};
 
+// Citations are also carried on response text blocks
+pub use crate::types::messages::TextCitation;
+
 /// Main stream event type that encompasses all possible streaming events.
 ///
 /// This is the primary type you'll work with when processing streaming responses.
@@ -68,6 +71,12 @@ pub enum MessageStreamEvent {
         /// Index of the content block that finished
         index: usize,
     },
+
+    /// An event type not known to this version of the SDK, kept as raw JSON.
+    ///
+    /// Unknown events are passed through rather than failing the stream.
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
 /// Delta updates for message-level information during streaming.
@@ -142,72 +151,10 @@ pub enum ContentBlockDelta {
         /// The signature string
         signature: String,
     },
-}
-
-/// Citation information for text blocks.
-///
-/// Citations provide source attribution for generated text,
-/// with different types depending on the source document.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(tag = "type")]
-pub enum TextCitation {
-    /// Citation pointing to character locations in plain text.
-    #[serde(rename = "char_location")]
-    CharLocation {
-        /// The text being cited
-        cited_text: String,
-        /// Index of the source document
-        document_index: usize,
-        /// Title of the source document (may be null)
-        document_title: Option<String>,
-        /// Starting character index
-        start_char_index: usize,
-        /// Ending character index
-        end_char_index: usize,
-    },
-
-    /// Citation pointing to page locations in PDFs.
-    #[serde(rename = "page_location")]
-    PageLocation {
-        /// The text being cited
-        cited_text: String,
-        /// Index of the source document
-        document_index: usize,
-        /// Title of the source document (may be null)
-        document_title: Option<String>,
-        /// Starting page number
-        start_page_number: usize,
-        /// Ending page number
-        end_page_number: usize,
-    },
 
-    /// Citation pointing to content block locations.
-    #[serde(rename = "content_block_location")]
-    ContentBlockLocation {
-        /// The text being cited
-        cited_text: String,
-        /// Index of the source document
-        document_index: usize,
-        /// Title of the source document (may be null)
-        document_title: Option<String>,
-        /// Starting content block index
-        start_block_index: usize,
-        /// Ending content block index
-        end_block_index: usize,
-    },
-
-    /// Citation pointing to web search results.
-    #[serde(rename = "web_search_result_location")]
-    WebSearchResultLocation {
-        /// The text being cited
-        cited_text: String,
-        /// Encrypted index for the search result
-        encrypted_index: String,
-        /// Title of the web page (may be null)
-        title: Option<String>,
-        /// URL of the web page
-        url: String,
-    },
+    /// A delta type not known to this version of the SDK, kept as raw JSON.
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
 /// Type aliases for clarity and compatibility with the main API types.
@@ -31,6 +31,9 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use crate::types::shared::CacheControl;
+use crate::types::schema::SchemaViolation;
+use crate::types::messages::{ContentBlockParam, DocumentSource, MessageContent};
 
 /// A tool definition for function calling.
 ///
@@ -46,6 +49,10 @@ pub struct Tool {
     
     /// JSON schema definition for the tool's input parameters.
     pub input_schema: ToolInputSchema,
+    
+    /// Prompt cache breakpoint; marking the last tool caches all tool definitions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
 }
 
 /// JSON schema for tool input parameters.
@@ -61,7 +68,7 @@ pub struct ToolInputSchema {
     pub properties: Map<String, Value>,
     
     /// List of required parameter names.
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub required: Vec<String>,
     
     /// Additional schema properties.
@@ -77,18 +84,34 @@ pub struct ToolInputSchema {
 pub enum ToolChoice {
     /// Let Claude automatically decide whether and which tools to use.
     #[serde(rename = "auto")]
-    Auto,
+    Auto {
+        /// Use at most one tool per response.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        disable_parallel_tool_use: Option<bool>,
+    },
     
     /// Claude must use one of the available tools.
     #[serde(rename = "any")]
-    Any,
+    Any {
+        /// Use exactly one tool per response.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        disable_parallel_tool_use: Option<bool>,
+    },
     
     /// Force Claude to use a specific tool.
     #[serde(rename = "tool")]
     Tool {
         /// The name of the tool that must be used.
         name: String,
+        
+        /// Use exactly one tool per response.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        disable_parallel_tool_use: Option<bool>,
     },
+    
+    /// Keep tool definitions in context but don't allow any tool calls.
+    #[serde(rename = "none")]
+    None,
 }
 
 /// A tool use request from Claude.
@@ -155,6 +178,13 @@ pub enum ToolResultBlock {
         /// Image source information.
         source: ImageSource,
     },
+    
+    /// Document content block, e.g. a generated PDF report.
+    #[serde(rename = "document")]
+    Document {
+        /// Document source information.
+        source: DocumentSource,
+    },
 }
 
 /// Image source for tool results.
@@ -181,6 +211,7 @@ pub struct ToolBuilder {
     properties: Map<String, Value>,
     required: Vec<String>,
     additional: Map<String, Value>,
+    cache_control: Option<CacheControl>,
 }
 
 impl ToolBuilder {
@@ -192,6 +223,7 @@ impl ToolBuilder {
             properties: Map::new(),
             required: Vec::new(),
             additional: Map::new(),
+            cache_control: None,
         }
     }
     
@@ -264,6 +296,12 @@ impl ToolBuilder {
         self
     }
     
+    /// Mark the tool as a prompt cache breakpoint.
+    pub fn cache_control(mut self, cache_control: CacheControl) -> Self {
+        self.cache_control = Some(cache_control);
+        self
+    }
+    
     /// Build the tool definition.
     pub fn build(self) -> Tool {
         Tool {
@@ -275,6 +313,7 @@ impl ToolBuilder {
                 required: self.required,
                 additional: self.additional,
             },
+            cache_control: self.cache_control,
         }
     }
 }
@@ -288,6 +327,7 @@ impl Tool {
             properties: Map::new(),
             required: Vec::new(),
             additional: Map::new(),
+            cache_control: None,
         }
     }
     
@@ -296,75 +336,123 @@ impl Tool {
         ToolBuilder::new(name, description)
     }
     
-    /// Validate if the given input matches this tool's schema.
-    pub fn validate_input(&self, input: &Value) -> Result<(), ToolValidationError> {
-        // Basic validation - check required fields
-        if let Value::Object(input_obj) = input {
-            for required_field in &self.input_schema.required {
-                if !input_obj.contains_key(required_field) {
-                    return Err(ToolValidationError::MissingRequiredField {
-                        field: required_field.clone(),
-                        tool: self.name.clone(),
-                    });
-                }
-            }
-            
-            // Check field types
-            for (field_name, field_value) in input_obj {
-                if let Some(property_schema) = self.input_schema.properties.get(field_name) {
-                    self.validate_field_type(field_name, field_value, property_schema)?;
-                }
-            }
-            
-            Ok(())
-        } else {
-            Err(ToolValidationError::InvalidInputType {
-                expected: "object".to_string(),
-                actual: input.to_string(),
-                tool: self.name.clone(),
-            })
+    /// Create a tool from a complete JSON Schema for its input.
+    ///
+    /// `properties` and `required` are lifted into the input schema, `$schema`
+    /// is dropped and every other keyword (e.g. `$defs`) is kept as is.
+    pub fn from_json_schema(name: impl Into<String>, description: impl Into<String>, schema: Value) -> Self {
+        let mut schema = match schema {
+            Value::Object(schema) => schema,
+            _ => Map::new(),
+        };
+        schema.remove("$schema");
+        schema.remove("type");
+        let properties = match schema.remove("properties") {
+            Some(Value::Object(properties)) => properties,
+            _ => Map::new(),
+        };
+        let required = match schema.remove("required") {
+            Some(Value::Array(required)) => required
+                .into_iter()
+                .filter_map(|field| field.as_str().map(String::from))
+                .collect(),
+            _ => Vec::new(),
+        };
+        
+        Tool {
+            name: name.into(),
+            description: description.into(),
+            input_schema: ToolInputSchema {
+                schema_type: "object".to_string(),
+                properties,
+                required,
+                additional: schema,
+            },
+            cache_control: None,
         }
     }
     
-    fn validate_field_type(&self, field_name: &str, value: &Value, schema: &Value) -> Result<(), ToolValidationError> {
-        if let Some(expected_type) = schema.get("type").and_then(|t| t.as_str()) {
-            let actual_type = match value {
-                Value::Null => "null",
-                Value::Bool(_) => "boolean",
-                Value::Number(_) => "number",
-                Value::String(_) => "string",
-                Value::Array(_) => "array",
-                Value::Object(_) => "object",
-            };
-            
-            if expected_type != actual_type {
-                return Err(ToolValidationError::InvalidFieldType {
-                    field: field_name.to_string(),
-                    expected: expected_type.to_string(),
-                    actual: actual_type.to_string(),
-                    tool: self.name.clone(),
-                });
-            }
-        }
+    /// Create a tool whose input schema is derived from `T`.
+    ///
+    /// The tool is named after the type and described by its doc comment.
+    /// `T` should serialize as a JSON object.
+    #[cfg(feature = "schemars")]
+    pub fn for_type<T: schemars::JsonSchema>() -> Self {
+        let schema: Value = schemars::schema_for!(T).into();
+        let name: String = T::schema_name()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+            .take(64)
+            .collect();
+        let description = schema
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or("Record the response as structured data.")
+            .to_string();
         
-        Ok(())
+        let mut tool = Self::from_json_schema(name, description, schema);
+        tool.input_schema.additional.remove("title");
+        tool.input_schema.additional.remove("description");
+        tool
+    }
+    
+    /// Validate the given input against this tool's JSON schema.
+    ///
+    /// Reports every violation, each with the JSON pointer of the offending value.
+    /// See [`schema`](super::schema) for the supported keywords.
+    pub fn validate_input(&self, input: &Value) -> Result<(), ToolValidationError> {
+        let schema = serde_json::to_value(&self.input_schema).unwrap_or(Value::Bool(true));
+        let violations = super::schema::validate(&schema, input);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ToolValidationError::InvalidInput { tool: self.name.clone(), violations })
+        }
     }
 }
 
 impl ToolChoice {
     /// Create an auto tool choice.
     pub fn auto() -> Self {
-        Self::Auto
+        Self::Auto { disable_parallel_tool_use: None }
     }
     
     /// Create an any tool choice.
     pub fn any() -> Self {
-        Self::Any
+        Self::Any { disable_parallel_tool_use: None }
     }
     
     /// Create a specific tool choice.
     pub fn tool(name: impl Into<String>) -> Self {
-        Self::Tool { name: name.into() }
+        Self::Tool { name: name.into(), disable_parallel_tool_use: None }
+    }
+    
+    /// Create a tool choice that forbids tool calls.
+    pub fn none() -> Self {
+        Self::None
+    }
+    
+    /// Limit Claude to a single tool call per response.
+    ///
+    /// Has no effect on [`ToolChoice::None`], which allows no tool calls at all.
+    pub fn disable_parallel_tool_use(mut self) -> Self {
+        match &mut self {
+            Self::Auto { disable_parallel_tool_use }
+            | Self::Any { disable_parallel_tool_use }
+            | Self::Tool { disable_parallel_tool_use, .. } => *disable_parallel_tool_use = Some(true),
+            Self::None => {}
+        }
+        self
+    }
+    
+    /// Whether parallel tool use has been disabled.
+    pub fn is_parallel_tool_use_disabled(&self) -> bool {
+        match self {
+            Self::Auto { disable_parallel_tool_use }
+            | Self::Any { disable_parallel_tool_use }
+            | Self::Tool { disable_parallel_tool_use, .. } => disable_parallel_tool_use.unwrap_or(false),
+            Self::None => false,
+        }
     }
 }
 
@@ -421,22 +509,97 @@ impl ToolResultBlock {
             },
         }
     }
+    
+    /// Create a document content block from base64-encoded PDF data.
+    pub fn document_pdf_base64(data: impl Into<String>) -> Self {
+        Self::Document {
+            source: DocumentSource::Base64 {
+                media_type: "application/pdf".to_string(),
+                data: data.into(),
+            },
+        }
+    }
+    
+    /// Create a document content block from plain text.
+    pub fn document_text(text: impl Into<String>) -> Self {
+        Self::Document {
+            source: DocumentSource::Text {
+                media_type: "text/plain".to_string(),
+                data: text.into(),
+            },
+        }
+    }
+}
+
+impl From<ToolResultBlock> for ContentBlockParam {
+    fn from(block: ToolResultBlock) -> Self {
+        match block {
+            ToolResultBlock::Text { text } => ContentBlockParam::text(text),
+            ToolResultBlock::Image { source: ImageSource::Base64 { media_type, data } } => {
+                ContentBlockParam::image_base64(media_type, data)
+            }
+            ToolResultBlock::Document { source } => ContentBlockParam::document(source),
+        }
+    }
+}
+
+impl From<ToolResultContent> for MessageContent {
+    fn from(content: ToolResultContent) -> Self {
+        match content {
+            ToolResultContent::Text(text) => MessageContent::Text(text),
+            // The API only accepts text or blocks, so JSON is sent as its text form
+            ToolResultContent::Json(json) => MessageContent::Text(json.to_string()),
+            ToolResultContent::Blocks(blocks) => {
+                MessageContent::Blocks(blocks.into_iter().map(ContentBlockParam::from).collect())
+            }
+        }
+    }
+}
+
+impl From<ToolResult> for ContentBlockParam {
+    fn from(result: ToolResult) -> Self {
+        ContentBlockParam::ToolResult {
+            tool_use_id: result.tool_use_id,
+            content: Some(result.content.into()),
+            is_error: result.is_error,
+            cache_control: None,
+        }
+    }
 }
 
 /// Tool validation errors.
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum ToolValidationError {
-    /// A required field is missing from the input.
-    #[error("Missing required field '{field}' for tool '{tool}'")]
-    MissingRequiredField { field: String, tool: String },
-    
-    /// Invalid input type (expected object).
-    #[error("Invalid input type for tool '{tool}': expected {expected}, got {actual}")]
-    InvalidInputType { expected: String, actual: String, tool: String },
-    
-    /// Invalid field type.
-    #[error("Invalid type for field '{field}' in tool '{tool}': expected {expected}, got {actual}")]
-    InvalidFieldType { field: String, expected: String, actual: String, tool: String },
+    /// The input doesn't match the tool's input schema.
+    #[error("Invalid input for tool '{tool}': {}", format_violations(.violations))]
+    InvalidInput { tool: String, violations: Vec<SchemaViolation> },
+}
+
+impl ToolValidationError {
+    /// The individual schema violations.
+    pub fn violations(&self) -> &[SchemaViolation] {
+        match self {
+            Self::InvalidInput { violations, .. } => violations,
+        }
+    }
+
+    /// A structured error result to send back to Claude, so it can correct its input.
+    pub fn to_tool_result(&self, tool_use_id: impl Into<String>) -> ToolResult {
+        let Self::InvalidInput { tool, violations } = self;
+        ToolResult {
+            tool_use_id: tool_use_id.into(),
+            content: ToolResultContent::Json(serde_json::json!({
+                "error": "invalid_input",
+                "message": format!("Input does not match the schema for tool '{}'", tool),
+                "violations": violations,
+            })),
+            is_error: Some(true),
+        }
+    }
+}
+
+fn format_violations(violations: &[SchemaViolation]) -> String {
+    violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
 }
 
 /// Server-side tools provided by Anthropic.
@@ -449,61 +612,300 @@ pub enum ServerTool {
     /// Web search tool for retrieving current information.
     #[serde(rename = "web_search_20250305")]
     WebSearch {
-        /// Optional search parameters.
-        #[serde(skip_serializing_if = "Option::is_none")]
-        parameters: Option<WebSearchParameters>,
+        /// Name the model uses to call the tool; always `web_search`.
+        #[serde(default = "web_search_name")]
+        name: String,
+
+        /// Search limits and filters.
+        #[serde(flatten)]
+        parameters: WebSearchParameters,
+
+        /// Optional cache breakpoint for this tool definition.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
 }
 
+fn web_search_name() -> String {
+    "web_search".to_string()
+}
+
 /// Parameters for the web search server tool.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct WebSearchParameters {
-    /// Maximum number of search results to return.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    max_results: Option<u32>,
-    
-    /// Search language preference.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    language: Option<String>,
-    
-    /// Geographic region for search results.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    region: Option<String>,
+    /// Maximum number of searches the model may perform in one request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_uses: Option<u32>,
+
+    /// Only include results from these domains.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    allowed_domains: Option<Vec<String>>,
+
+    /// Never include results from these domains.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    blocked_domains: Option<Vec<String>>,
+
+    /// Approximate location used to localize results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_location: Option<UserLocation>,
+}
+
+/// Approximate user location for localizing web search results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserLocation {
+    /// Location type; always `approximate`.
+    #[serde(rename = "type")]
+    pub location_type: String,
+
+    /// City name, e.g. `San Francisco`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+
+    /// Region or state, e.g. `California`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// Two-letter ISO country code, e.g. `US`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+
+    /// IANA time zone, e.g. `America/Los_Angeles`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
 }
 
 impl ServerTool {
     /// Create a web search tool with default parameters.
     pub fn web_search() -> Self {
-        Self::WebSearch { parameters: None }
+        Self::web_search_with_params(WebSearchParameters::default())
     }
     
     /// Create a web search tool with custom parameters.
     pub fn web_search_with_params(parameters: WebSearchParameters) -> Self {
-        Self::WebSearch { parameters: Some(parameters) }
+        Self::WebSearch {
+            name: web_search_name(),
+            parameters,
+            cache_control: None,
+        }
+    }
+
+    /// Name the model uses to call this tool.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::WebSearch { name, .. } => name,
+        }
+    }
+
+    /// Mark this tool definition as a cache breakpoint.
+    pub fn cache_control(mut self, cache_control: CacheControl) -> Self {
+        match &mut self {
+            Self::WebSearch { cache_control: slot, .. } => *slot = Some(cache_control),
+        }
+        self
     }
 }
 
 impl WebSearchParameters {
-    /// Create web search parameters with maximum results.
-    pub fn with_max_results(max_results: u32) -> Self {
+    /// Create empty web search parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create web search parameters limiting the number of searches.
+    ///
+    /// Despite the name this limits searches, not results.
+    #[deprecated(note = "use WebSearchParameters::new().max_uses(n)")]
+    pub fn with_max_results(max_uses: u32) -> Self {
+        Self::new().max_uses(max_uses)
+    }
+
+    /// Limit the number of searches per request.
+    pub fn max_uses(mut self, max_uses: u32) -> Self {
+        self.max_uses = Some(max_uses);
+        self
+    }
+
+    /// Restrict results to the given domains.
+    pub fn allowed_domains<I, S>(mut self, domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_domains = Some(domains.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Exclude results from the given domains.
+    pub fn blocked_domains<I, S>(mut self, domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.blocked_domains = Some(domains.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Localize results to the given location.
+    pub fn user_location(mut self, user_location: UserLocation) -> Self {
+        self.user_location = Some(user_location);
+        self
+    }
+}
+
+impl UserLocation {
+    /// Create an approximate location with no fields set.
+    pub fn approximate() -> Self {
         Self {
-            max_results: Some(max_results),
-            language: None,
+            location_type: "approximate".to_string(),
+            city: None,
             region: None,
+            country: None,
+            timezone: None,
         }
     }
-    
-    /// Set the search language.
-    pub fn language(mut self, language: impl Into<String>) -> Self {
-        self.language = Some(language.into());
+
+    /// Set the city.
+    pub fn city(mut self, city: impl Into<String>) -> Self {
+        self.city = Some(city.into());
         self
     }
-    
-    /// Set the search region.
+
+    /// Set the region or state.
     pub fn region(mut self, region: impl Into<String>) -> Self {
         self.region = Some(region.into());
         self
     }
+
+    /// Set the two-letter country code.
+    pub fn country(mut self, country: impl Into<String>) -> Self {
+        self.country = Some(country.into());
+        self
+    }
+
+    /// Set the IANA time zone.
+    pub fn timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = Some(timezone.into());
+        self
+    }
+}
+
+/// A tool definition accepted by the Messages API: either a custom tool
+/// executed by the client or a server tool executed by Anthropic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolUnion {
+    /// A client-side tool with an input schema.
+    Custom(Tool),
+
+    /// A tool executed on Anthropic's servers.
+    Server(ServerTool),
+}
+
+impl ToolUnion {
+    /// Name the model uses to call this tool.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Custom(tool) => &tool.name,
+            Self::Server(tool) => tool.name(),
+        }
+    }
+
+    /// Mark this tool definition as a cache breakpoint.
+    pub fn cache_control(self, cache_control: CacheControl) -> Self {
+        match self {
+            Self::Custom(mut tool) => {
+                tool.cache_control = Some(cache_control);
+                Self::Custom(tool)
+            }
+            Self::Server(tool) => Self::Server(tool.cache_control(cache_control)),
+        }
+    }
+}
+
+impl From<Tool> for ToolUnion {
+    fn from(tool: Tool) -> Self {
+        Self::Custom(tool)
+    }
+}
+
+impl From<ServerTool> for ToolUnion {
+    fn from(tool: ServerTool) -> Self {
+        Self::Server(tool)
+    }
+}
+
+/// A single page returned by the web search tool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "web_search_result")]
+pub struct WebSearchResult {
+    /// URL of the page.
+    pub url: String,
+
+    /// Title of the page.
+    pub title: String,
+
+    /// Encrypted page content; must be passed back unchanged in multi-turn conversations.
+    pub encrypted_content: String,
+
+    /// When the page was last updated, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_age: Option<String>,
+}
+
+/// Error returned by the web search tool in place of results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "web_search_tool_result_error")]
+pub struct WebSearchToolResultError {
+    /// Why the search failed.
+    pub error_code: WebSearchErrorCode,
+}
+
+/// Reasons a web search can fail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebSearchErrorCode {
+    /// The query was malformed.
+    InvalidToolInput,
+    /// The search service was unavailable.
+    Unavailable,
+    /// The request exceeded the tool's `max_uses`.
+    MaxUsesExceeded,
+    /// The search service was rate limited.
+    TooManyRequests,
+    /// The query was too long.
+    QueryTooLong,
+    /// An error code not known to this version of the SDK.
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// Content of a `web_search_tool_result` block: results or an error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WebSearchToolResultContent {
+    /// Pages found by the search.
+    Results(Vec<WebSearchResult>),
+
+    /// The search failed.
+    Error(WebSearchToolResultError),
+}
+
+impl WebSearchToolResultContent {
+    /// The search results, or an empty slice if the search failed.
+    pub fn results(&self) -> &[WebSearchResult] {
+        match self {
+            Self::Results(results) => results,
+            Self::Error(_) => &[],
+        }
+    }
+
+    /// The error code, if the search failed.
+    pub fn error_code(&self) -> Option<WebSearchErrorCode> {
+        match self {
+            Self::Results(_) => None,
+            Self::Error(error) => Some(error.error_code.clone()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -552,6 +954,50 @@ mod tests {
             "required_field": 123
         });
         assert!(tool.validate_input(&wrong_type_input).is_err());
+
+        // Integers satisfy "integer", fractions don't
+        let tool = Tool::new("test_tool", "Test tool")
+            .parameter("count", "integer", "Count")
+            .build();
+        assert!(tool.validate_input(&json!({"count": 3})).is_ok());
+        assert!(tool.validate_input(&json!({"count": 3.5})).is_err());
+    }
+
+    #[test]
+    fn test_tool_from_json_schema() {
+        let tool = Tool::from_json_schema("lookup", "Look something up", json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {"query": {"type": "string"}},
+            "required": ["query"],
+            "$defs": {"Unit": {"type": "string"}},
+        }));
+        assert_eq!(serde_json::to_value(&tool.input_schema).unwrap(), json!({
+            "type": "object",
+            "properties": {"query": {"type": "string"}},
+            "required": ["query"],
+            "$defs": {"Unit": {"type": "string"}},
+        }));
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_tool_for_type() {
+        /// A person mentioned in the text.
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Person {
+            name: String,
+            age: Option<u32>,
+        }
+
+        let tool = Tool::for_type::<Person>();
+        assert_eq!(tool.name, "Person");
+        assert_eq!(tool.description, "A person mentioned in the text.");
+        assert_eq!(tool.input_schema.required, vec!["name".to_string()]);
+        assert!(tool.input_schema.properties.contains_key("age"));
+        assert!(!tool.input_schema.additional.contains_key("$schema"));
+        assert!(!tool.input_schema.additional.contains_key("title"));
     }
 
     #[test]
@@ -563,6 +1009,17 @@ mod tests {
         let tool_choice = ToolChoice::tool("get_weather");
         let json = serde_json::to_value(&tool_choice).unwrap();
         assert_eq!(json, json!({"type": "tool", "name": "get_weather"}));
+
+        let none_choice = ToolChoice::none();
+        assert_eq!(serde_json::to_value(&none_choice).unwrap(), json!({"type": "none"}));
+        assert!(!none_choice.clone().disable_parallel_tool_use().is_parallel_tool_use_disabled());
+
+        let sequential = ToolChoice::any().disable_parallel_tool_use();
+        assert!(sequential.is_parallel_tool_use_disabled());
+        let json = serde_json::to_value(&sequential).unwrap();
+        assert_eq!(json, json!({"type": "any", "disable_parallel_tool_use": true}));
+        assert_eq!(serde_json::from_value::<ToolChoice>(json).unwrap(), sequential);
+        assert_eq!(serde_json::from_value::<ToolChoice>(json!({"type": "auto"})).unwrap(), ToolChoice::auto());
     }
 
     #[test]
@@ -583,23 +1040,100 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tool_result_to_content_block() {
+        let result = ToolResult::with_blocks("toolu_1", vec![
+            ToolResultBlock::text("Here is the chart"),
+            ToolResultBlock::image_base64("image/png", "iVBORw0KGgo="),
+            ToolResultBlock::document_text("raw data"),
+        ]);
+        let block: ContentBlockParam = result.into();
+        assert_eq!(serde_json::to_value(&block).unwrap(), json!({
+            "type": "tool_result",
+            "tool_use_id": "toolu_1",
+            "content": [
+                {"type": "text", "text": "Here is the chart"},
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo="}},
+                {"type": "document", "source": {"type": "text", "media_type": "text/plain", "data": "raw data"}},
+            ],
+        }));
+
+        let json_result: ContentBlockParam = ToolResult::success_json("toolu_2", json!({"temp": 22})).into();
+        assert_eq!(serde_json::to_value(&json_result).unwrap()["content"], r#"{"temp":22}"#);
+
+        let error: ContentBlockParam = ToolResult::error("toolu_3", "boom").into();
+        assert_eq!(serde_json::to_value(&error).unwrap()["is_error"], true);
+    }
+
     #[test]
     fn test_server_tool_creation() {
         let web_search = ServerTool::web_search();
-        assert!(matches!(web_search, ServerTool::WebSearch { parameters: None }));
+        assert_eq!(web_search.name(), "web_search");
+        assert_eq!(serde_json::to_value(&web_search).unwrap(), json!({
+            "type": "web_search_20250305",
+            "name": "web_search",
+        }));
 
-        let params = WebSearchParameters::with_max_results(10)
-            .language("en")
-            .region("US");
+        let params = WebSearchParameters::new()
+            .max_uses(5)
+            .allowed_domains(["example.com", "docs.rs"])
+            .user_location(UserLocation::approximate().city("San Francisco").country("US"));
         let web_search_with_params = ServerTool::web_search_with_params(params);
-        
-        if let ServerTool::WebSearch { parameters: Some(p) } = web_search_with_params {
-            assert_eq!(p.max_results, Some(10));
-            assert_eq!(p.language, Some("en".to_string()));
-            assert_eq!(p.region, Some("US".to_string()));
-        } else {
-            panic!("Expected web search with parameters");
-        }
+        let value = serde_json::to_value(&web_search_with_params).unwrap();
+        assert_eq!(value, json!({
+            "type": "web_search_20250305",
+            "name": "web_search",
+            "max_uses": 5,
+            "allowed_domains": ["example.com", "docs.rs"],
+            "user_location": {"type": "approximate", "city": "San Francisco", "country": "US"},
+        }));
+
+        let parsed: ServerTool = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, web_search_with_params);
+    }
+
+    #[test]
+    fn test_tool_union_serialization() {
+        let tools: Vec<ToolUnion> = vec![
+            Tool::new("calculate", "Do math").build().into(),
+            ServerTool::web_search().cache_control(CacheControl::ephemeral()).into(),
+        ];
+        let value = serde_json::to_value(&tools).unwrap();
+        assert_eq!(value[0]["name"], "calculate");
+        assert_eq!(value[1]["type"], "web_search_20250305");
+        assert_eq!(value[1]["cache_control"], json!({"type": "ephemeral"}));
+
+        let parsed: Vec<ToolUnion> = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, tools);
+        assert!(matches!(parsed[1], ToolUnion::Server(_)));
+    }
+
+    #[test]
+    fn test_web_search_result_content() {
+        let results: WebSearchToolResultContent = serde_json::from_value(json!([{
+            "type": "web_search_result",
+            "url": "https://example.com",
+            "title": "Example",
+            "encrypted_content": "enc",
+            "page_age": "April 30, 2025",
+        }])).unwrap();
+        assert_eq!(results.results()[0].url, "https://example.com");
+        assert_eq!(results.error_code(), None);
+
+        let error: WebSearchToolResultContent = serde_json::from_value(json!({
+            "type": "web_search_tool_result_error",
+            "error_code": "max_uses_exceeded",
+        })).unwrap();
+        assert!(error.results().is_empty());
+        assert_eq!(error.error_code(), Some(WebSearchErrorCode::MaxUsesExceeded));
+
+        // New error codes still parse, keeping the raw value
+        let error: WebSearchToolResultContent = serde_json::from_value(json!({
+            "type": "web_search_tool_result_error",
+            "error_code": "request_too_large",
+        })).unwrap();
+        assert_eq!(error.error_code(), Some(WebSearchErrorCode::Other("request_too_large".to_string())));
+        assert_eq!(serde_json::to_value(&error).unwrap()["error_code"], "request_too_large");
     }
 
     #[test]
@@ -0,0 +1,360 @@
+//! Pre-flight checks for message requests.
+//!
+//! [`MessageCreateParams::validate`] catches requests the API would reject
+//! with a 400, without the network round trip.
+
+use std::collections::HashSet;
+
+use super::messages::{ContentBlockParam, MessageContent, MessageCreateParams, MessageParam, Role, ThinkingConfig};
+use super::models::max_output_tokens;
+use super::tools::ToolChoice;
+
+/// Smallest thinking budget the API accepts
+const MIN_THINKING_BUDGET: u32 = 1_024;
+
+/// Why a request would be rejected; message indices refer to `params.messages`.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RequestValidationError {
+    #[error("messages must not be empty")]
+    NoMessages,
+
+    #[error("messages[0] must have the user role")]
+    FirstMessageNotUser,
+
+    #[error("messages[{index}] has empty content")]
+    EmptyContent { index: usize },
+
+    #[error("messages[{index}] has a tool_result for '{tool_use_id}' without a matching tool_use in the previous message")]
+    UnexpectedToolResult { index: usize, tool_use_id: String },
+
+    #[error("messages[{index}] has a tool_use '{tool_use_id}' that is not answered by a tool_result in the next message")]
+    MissingToolResult { index: usize, tool_use_id: String },
+
+    #[error("messages[{index}] has tool_result blocks after other content; they must come first")]
+    ToolResultNotFirst { index: usize },
+
+    #[error("temperature must be between 0 and 1, got {value}")]
+    TemperatureOutOfRange { value: f32 },
+
+    #[error("top_p must be between 0 and 1, got {value}")]
+    TopPOutOfRange { value: f32 },
+
+    #[error("max_tokens must be at least 1")]
+    ZeroMaxTokens,
+
+    #[error("max_tokens {max_tokens} exceeds the {limit} token output limit of {model}")]
+    MaxTokensExceedsLimit { max_tokens: u32, limit: u32, model: String },
+
+    #[error("thinking budget_tokens must be at least {MIN_THINKING_BUDGET} and less than max_tokens {max_tokens}, got {budget_tokens}")]
+    InvalidThinkingBudget { budget_tokens: u32, max_tokens: u32 },
+
+    #[error("stop_sequences[{index}] must contain non-whitespace characters")]
+    BlankStopSequence { index: usize },
+
+    #[error("tool name '{name}' is used by more than one tool")]
+    DuplicateToolName { name: String },
+
+    #[error("tool_choice names tool '{name}', which is not in tools")]
+    UnknownToolChoice { name: String },
+}
+
+impl RequestValidationError {
+    /// Index of the offending message, for errors about a specific message
+    pub fn message_index(&self) -> Option<usize> {
+        match self {
+            Self::FirstMessageNotUser => Some(0),
+            Self::EmptyContent { index }
+            | Self::UnexpectedToolResult { index, .. }
+            | Self::MissingToolResult { index, .. }
+            | Self::ToolResultNotFirst { index } => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+impl MessageCreateParams {
+    /// Check the request for mistakes the API would reject, returning the first one found
+    ///
+    /// Covers message ordering, empty content, tool_use/tool_result pairing,
+    /// sampling ranges, `max_tokens` against known model limits, the thinking
+    /// budget, stop sequences and tool names. Passing doesn't guarantee the API
+    /// accepts the request. Use [`MessagesResource::validate_requests`] to run it
+    /// before every request.
+    ///
+    /// [`MessagesResource::validate_requests`]: crate::resources::MessagesResource::validate_requests
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use anthropic_sdk::types::{MessageCreateBuilder, RequestValidationError};
+    ///
+    /// let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+    ///     .assistant("Hello!")
+    ///     .build();
+    /// assert_eq!(params.validate(), Err(RequestValidationError::FirstMessageNotUser));
+    /// ```
+    pub fn validate(&self) -> Result<(), RequestValidationError> {
+        self.validate_messages()?;
+        self.validate_sampling()?;
+        self.validate_tools()
+    }
+
+    fn validate_messages(&self) -> Result<(), RequestValidationError> {
+        let first = self.messages.first().ok_or(RequestValidationError::NoMessages)?;
+        if first.role != Role::User {
+            return Err(RequestValidationError::FirstMessageNotUser);
+        }
+
+        let last = self.messages.len() - 1;
+        for (index, message) in self.messages.iter().enumerate() {
+            // Only a final assistant message (a prefill) may be empty
+            let may_be_empty = index == last && message.role == Role::Assistant;
+            if !may_be_empty && is_empty(&message.content) {
+                return Err(RequestValidationError::EmptyContent { index });
+            }
+
+            let previous_tool_uses = match index.checked_sub(1).map(|previous| &self.messages[previous]) {
+                Some(previous) if previous.role == Role::Assistant => tool_use_ids(previous),
+                _ => Vec::new(),
+            };
+            let results = tool_result_ids(message);
+
+            if message.role == Role::User {
+                if let Some(tool_use_id) = results.iter().find(|id| !previous_tool_uses.contains(id)) {
+                    return Err(RequestValidationError::UnexpectedToolResult {
+                        index,
+                        tool_use_id: tool_use_id.to_string(),
+                    });
+                }
+                if let MessageContent::Blocks(blocks) = &message.content {
+                    let first_other = blocks.iter().position(|block| !matches!(block, ContentBlockParam::ToolResult { .. }));
+                    let last_result = blocks.iter().rposition(|block| matches!(block, ContentBlockParam::ToolResult { .. }));
+                    if let (Some(first_other), Some(last_result)) = (first_other, last_result) {
+                        if first_other < last_result {
+                            return Err(RequestValidationError::ToolResultNotFirst { index });
+                        }
+                    }
+                }
+            } else if let Some(tool_use_id) = results.first() {
+                return Err(RequestValidationError::UnexpectedToolResult { index, tool_use_id: tool_use_id.to_string() });
+            }
+
+            if index > 0 && !previous_tool_uses.is_empty() {
+                if let Some(tool_use_id) = previous_tool_uses.iter().find(|id| !results.contains(id)) {
+                    return Err(RequestValidationError::MissingToolResult {
+                        index: index - 1,
+                        tool_use_id: tool_use_id.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_sampling(&self) -> Result<(), RequestValidationError> {
+        if let Some(value) = self.temperature.filter(|value| !(0.0..=1.0).contains(value)) {
+            return Err(RequestValidationError::TemperatureOutOfRange { value });
+        }
+        if let Some(value) = self.top_p.filter(|value| !(0.0..=1.0).contains(value)) {
+            return Err(RequestValidationError::TopPOutOfRange { value });
+        }
+
+        if self.max_tokens == 0 {
+            return Err(RequestValidationError::ZeroMaxTokens);
+        }
+        if let Some(limit) = max_output_tokens(&self.model).filter(|limit| self.max_tokens > *limit) {
+            return Err(RequestValidationError::MaxTokensExceedsLimit {
+                max_tokens: self.max_tokens,
+                limit,
+                model: self.model.clone(),
+            });
+        }
+        if let Some(ThinkingConfig::Enabled { budget_tokens }) = self.thinking {
+            if budget_tokens < MIN_THINKING_BUDGET || budget_tokens >= self.max_tokens {
+                return Err(RequestValidationError::InvalidThinkingBudget { budget_tokens, max_tokens: self.max_tokens });
+            }
+        }
+
+        let stop_sequences = self.stop_sequences.as_deref().unwrap_or_default();
+        if let Some(index) = stop_sequences.iter().position(|sequence| sequence.trim().is_empty()) {
+            return Err(RequestValidationError::BlankStopSequence { index });
+        }
+        Ok(())
+    }
+
+    fn validate_tools(&self) -> Result<(), RequestValidationError> {
+        let tools = self.tools.as_deref().unwrap_or_default();
+        let mut names = HashSet::new();
+        for tool in tools {
+            if !names.insert(tool.name()) {
+                return Err(RequestValidationError::DuplicateToolName { name: tool.name().to_string() });
+            }
+        }
+
+        if let Some(ToolChoice::Tool { name, .. }) = &self.tool_choice {
+            if !names.contains(name.as_str()) {
+                return Err(RequestValidationError::UnknownToolChoice { name: name.clone() });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_empty(content: &MessageContent) -> bool {
+    match content {
+        MessageContent::Text(text) => text.is_empty(),
+        MessageContent::Blocks(blocks) => {
+            blocks.is_empty()
+                || blocks.iter().any(|block| matches!(block, ContentBlockParam::Text { text, .. } if text.is_empty()))
+        }
+    }
+}
+
+fn tool_use_ids(message: &MessageParam) -> Vec<&str> {
+    match &message.content {
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlockParam::ToolUse { id, .. } => Some(id.as_str()),
+                _ => None,
+            })
+            .collect(),
+        MessageContent::Text(_) => Vec::new(),
+    }
+}
+
+fn tool_result_ids(message: &MessageParam) -> Vec<&str> {
+    match &message.content {
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlockParam::ToolResult { tool_use_id, .. } => Some(tool_use_id.as_str()),
+                _ => None,
+            })
+            .collect(),
+        MessageContent::Text(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MessageCreateBuilder, Tool, ToolResult};
+    use serde_json::json;
+
+    fn builder() -> MessageCreateBuilder {
+        MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+    }
+
+    fn tool_use(id: &str) -> ContentBlockParam {
+        ContentBlockParam::ToolUse { id: id.to_string(), name: "lookup".to_string(), input: json!({}), cache_control: None }
+    }
+
+    fn tool_result(id: &str) -> ContentBlockParam {
+        ToolResult::success(id, "done").into()
+    }
+
+    #[test]
+    fn test_valid_tool_round_trip() {
+        let params = builder()
+            .user("Look it up")
+            .assistant(MessageContent::Blocks(vec![tool_use("toolu_1"), tool_use("toolu_2")]))
+            .user(MessageContent::Blocks(vec![
+                tool_result("toolu_2"),
+                tool_result("toolu_1"),
+                ContentBlockParam::text("Thanks"),
+            ]))
+            .assistant("")
+            .tool(Tool::new("lookup", "Look something up").build())
+            .tool_choice(ToolChoice::tool("lookup"))
+            .build();
+        assert_eq!(params.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_message_errors_point_to_index() {
+        let error = builder().build().validate().unwrap_err();
+        assert_eq!(error, RequestValidationError::NoMessages);
+
+        let error = builder().user("Hi").assistant("").user("Hello?").build().validate().unwrap_err();
+        assert_eq!(error, RequestValidationError::EmptyContent { index: 1 });
+
+        let error = builder()
+            .user("Look it up")
+            .assistant(MessageContent::Blocks(vec![tool_use("toolu_1"), tool_use("toolu_2")]))
+            .user(MessageContent::Blocks(vec![tool_result("toolu_1")]))
+            .build()
+            .validate()
+            .unwrap_err();
+        assert_eq!(error, RequestValidationError::MissingToolResult { index: 1, tool_use_id: "toolu_2".to_string() });
+        assert_eq!(error.message_index(), Some(1));
+
+        let error = builder()
+            .user("Look it up")
+            .assistant("Sure")
+            .user(MessageContent::Blocks(vec![tool_result("toolu_9")]))
+            .build()
+            .validate()
+            .unwrap_err();
+        assert_eq!(error, RequestValidationError::UnexpectedToolResult { index: 2, tool_use_id: "toolu_9".to_string() });
+
+        let error = builder()
+            .user("Look it up")
+            .assistant(MessageContent::Blocks(vec![tool_use("toolu_1")]))
+            .user(MessageContent::Blocks(vec![ContentBlockParam::text("Here"), tool_result("toolu_1")]))
+            .build()
+            .validate()
+            .unwrap_err();
+        assert_eq!(error, RequestValidationError::ToolResultNotFirst { index: 2 });
+    }
+
+    #[test]
+    fn test_parameter_errors() {
+        let error = builder().user("Hi").temperature(1.5).build().validate().unwrap_err();
+        assert_eq!(error, RequestValidationError::TemperatureOutOfRange { value: 1.5 });
+
+        let error = builder().user("Hi").top_p(-0.1).build().validate().unwrap_err();
+        assert_eq!(error, RequestValidationError::TopPOutOfRange { value: -0.1 });
+
+        let error = MessageCreateBuilder::new("claude-3-5-sonnet-20241022", 10_000).user("Hi").build().validate().unwrap_err();
+        assert!(matches!(error, RequestValidationError::MaxTokensExceedsLimit { limit: 8_192, .. }));
+        // Unknown models are not limited
+        assert!(MessageCreateBuilder::new("claude-future-9", 500_000).user("Hi").build().validate().is_ok());
+
+        let error = builder().user("Hi").thinking(ThinkingConfig::enabled(2_048)).build().validate().unwrap_err();
+        assert_eq!(error, RequestValidationError::InvalidThinkingBudget { budget_tokens: 2_048, max_tokens: 1024 });
+
+        let error = builder().user("Hi").stop_sequences(vec!["END".to_string(), " \n".to_string()]).build().validate().unwrap_err();
+        assert_eq!(error, RequestValidationError::BlankStopSequence { index: 1 });
+    }
+
+    #[test]
+    fn test_tool_errors() {
+        let error = builder()
+            .user("Hi")
+            .tool(Tool::new("lookup", "Look something up").build())
+            .tool(Tool::new("lookup", "Look something else up").build())
+            .build()
+            .validate()
+            .unwrap_err();
+        assert_eq!(error, RequestValidationError::DuplicateToolName { name: "lookup".to_string() });
+
+        let error = builder().user("Hi").tool_choice(ToolChoice::tool("search")).build().validate().unwrap_err();
+        assert_eq!(error, RequestValidationError::UnknownToolChoice { name: "search".to_string() });
+    }
+
+    #[test]
+    fn test_max_output_tokens_lookup() {
+        assert_eq!(max_output_tokens("claude-opus-4-20250514"), Some(32_000));
+        assert_eq!(max_output_tokens("claude-opus-4-5"), Some(64_000));
+        assert_eq!(max_output_tokens("claude-3-haiku-20240307"), Some(4_096));
+        assert_eq!(max_output_tokens("claude-opus-4-9"), None);
+
+        // 128K output needs a beta header the request doesn't carry
+        assert_eq!(max_output_tokens("claude-3-7-sonnet-latest"), Some(64_000));
+        let error = MessageCreateBuilder::new("claude-3-7-sonnet-latest", 100_000).user("Hi").build().validate().unwrap_err();
+        assert!(matches!(error, RequestValidationError::MaxTokensExceedsLimit { limit: 64_000, .. }));
+
+        assert_eq!(crate::types::Model::Claude3Haiku20240307.max_output_tokens(), Some(4_096));
+    }
+}
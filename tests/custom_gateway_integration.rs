@@ -10,7 +10,7 @@ use tokio::time::timeout;
 fn extract_text_from_content(content: &[ContentBlock]) -> String {
     content.iter()
         .filter_map(|block| match block {
-            ContentBlock::Text { text } => Some(text.as_str()),
+            ContentBlock::Text { text, .. } => Some(text.as_str()),
             _ => None,
         })
         .collect::<Vec<_>>()
@@ -194,7 +194,7 @@ async fn test_streaming_response() {
                     println!("✅ Streaming completed successfully");
                     if !message.content.is_empty() {
                         if let Some(text) = message.content.iter().find_map(|block| {
-                            if let ContentBlock::Text { text } = block {
+                            if let ContentBlock::Text { text, .. } = block {
                                 Some(text)
                             } else {
                                 None
@@ -38,6 +38,15 @@ async fn test_config_with_invalid_url() {
     assert!(result.unwrap_err().to_string().contains("Base URL must start with http"));
 }
 
+#[tokio::test]
+async fn test_config_with_unix_socket_url() {
+    let config = ClientConfig::new("test-key").with_base_url("unix:///tmp/anthropic-gateway.sock");
+    assert!(config.validate().is_ok());
+
+    let config = ClientConfig::new("test-key").with_base_url("unix://");
+    assert!(config.validate().unwrap_err().to_string().contains("socket path"));
+}
+
 #[tokio::test]
 async fn test_client_test_connection() {
     let client = Anthropic::new("test-api-key").expect("Should create client");
@@ -0,0 +1,876 @@
+//! Offline integration tests against the in-process mock server.
+//!
+//! These cover the same scenarios as `custom_gateway_integration.rs` without
+//! needing network access or credentials. Run with:
+//!
+//! ```bash
+//! cargo test --features testing --test mock_server_integration
+//! ```
+
+use anthropic_sdk::testing::{fixtures, MockResponse, MockServer};
+use anthropic_sdk::types::{ContentBlock, StopReason};
+use anthropic_sdk::{
+    Anthropic, AnthropicError, AuthMethod, BatchCreateParams, BatchRequest, BatchResponse,
+    BatchResponseBody, BatchResult, BatchStatus, ContentBlockDelta, ContentBlockParam, ContinuationLimits,
+    Conversation, DropOldestTurns, FilePurpose, FileUploadParams, HistoryPolicy, McpServer,
+    MessageContent, MessageCreateBuilder, MessageParam, MessageStreamEvent, RequestValidationError, Role,
+    ServiceTier, ServiceTierPreference,
+};
+use futures::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+
+fn extract_text(content: &[ContentBlock]) -> String {
+    content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[tokio::test]
+async fn test_basic_message_creation() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue_message(MockResponse::text("Integration test successful"));
+
+    let client = server.client().unwrap();
+    let message = client
+        .messages()
+        .create(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hello!").build())
+        .await
+        .unwrap();
+
+    assert!(!message.id.is_empty());
+    assert_eq!(extract_text(&message.content), "Integration test successful");
+    assert!(message.usage.output_tokens > 0);
+}
+
+#[tokio::test]
+async fn test_request_parameters_are_sent() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    client
+        .messages()
+        .create(
+            MessageCreateBuilder::new("claude-3-5-haiku-latest", 50)
+                .system("You are a helpful assistant.")
+                .user("What is 2 + 2?")
+                .temperature(0.2)
+                .stop_sequences(vec!["END".to_string()])
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    let request = server.requests_to("POST", "/v1/messages").pop().unwrap();
+    let body = request.json();
+    assert_eq!(body["model"], "claude-3-5-haiku-latest");
+    assert_eq!(body["max_tokens"], 50);
+    assert_eq!(body["system"], "You are a helpful assistant.");
+    assert_eq!(body["stop_sequences"], json!(["END"]));
+    assert!((body["temperature"].as_f64().unwrap() - 0.2).abs() < 1e-6);
+    assert_eq!(request.header("anthropic-version"), Some("2023-06-01"));
+}
+
+#[tokio::test]
+async fn test_bearer_token_authentication() {
+    let server = MockServer::start().await.unwrap();
+    let config = server.config().with_auth_method(AuthMethod::Bearer);
+    let client = Anthropic::with_config(config).unwrap();
+
+    client
+        .messages()
+        .create(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 20).user("Hi").build())
+        .await
+        .unwrap();
+
+    let request = server.last_request().unwrap();
+    assert_eq!(
+        request.header("authorization"),
+        Some(format!("Bearer {}", anthropic_sdk::testing::MOCK_API_KEY).as_str())
+    );
+    assert_eq!(request.header("x-api-key"), None);
+}
+
+#[tokio::test]
+async fn test_streaming_response() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue_message(MockResponse::text_stream("one two three"));
+
+    let client = server.client().unwrap();
+    let mut stream = client
+        .messages()
+        .create_stream(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Count").stream(true).build())
+        .await
+        .unwrap();
+
+    let mut events = 0;
+    while let Some(event) = stream.next().await {
+        event.unwrap();
+        events += 1;
+    }
+    assert!(events >= 5, "expected a full event sequence, got {}", events);
+
+    let request = server.last_request().unwrap();
+    assert_eq!(request.json()["stream"], true);
+    assert_eq!(request.header("accept"), Some("text/event-stream"));
+    assert_eq!(request.header("x-api-key"), Some(anthropic_sdk::testing::MOCK_API_KEY));
+}
+
+#[tokio::test]
+async fn test_validate_requests_before_sending() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue_message(MockResponse::text("Hello"));
+
+    let client = server.client().unwrap();
+    let invalid = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").temperature(2.0).build();
+    let error = client.messages().validate_requests().create(invalid.clone()).await.unwrap_err();
+    assert!(
+        matches!(error, AnthropicError::InvalidRequest(RequestValidationError::TemperatureOutOfRange { .. })),
+        "{:?}",
+        error
+    );
+    assert!(client.messages().validate_requests().create_stream(invalid).await.is_err());
+    assert!(server.requests_to("POST", "/v1/messages").is_empty());
+
+    let valid = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").build();
+    client.messages().validate_requests().create(valid).await.unwrap();
+    assert_eq!(server.requests_to("POST", "/v1/messages").len(), 1);
+}
+
+#[tokio::test]
+async fn test_streaming_final_message() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue_message(MockResponse::text_stream("Streamed reply"));
+
+    let client = server.client().unwrap();
+    let stream = client
+        .messages()
+        .create_stream(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").stream(true).build())
+        .await
+        .unwrap();
+
+    let message = stream.final_message().await.unwrap();
+    assert_eq!(extract_text(&message.content), "Streamed reply");
+}
+
+#[tokio::test]
+async fn test_auto_continue_paused_turns() {
+    let server = MockServer::start().await.unwrap();
+    let search = |id: &str| ContentBlock::ServerToolUse {
+        id: id.to_string(),
+        name: "web_search".to_string(),
+        input: json!({"query": "rust"}),
+    };
+    server
+        .enqueue_message(MockResponse::message(fixtures::message(vec![search("srvtoolu_1")], StopReason::PauseTurn)))
+        .enqueue_message(MockResponse::message(fixtures::message(vec![search("srvtoolu_2")], StopReason::PauseTurn)))
+        .enqueue_message(MockResponse::text("Rust 2024 is out"))
+        .enqueue_message(MockResponse::message(fixtures::message(vec![search("srvtoolu_3")], StopReason::PauseTurn)))
+        .enqueue_message(MockResponse::message(fixtures::message(vec![search("srvtoolu_4")], StopReason::PauseTurn)));
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("What's new?").build();
+    let message = client.messages().auto_continue(5).create(params.clone()).await.unwrap();
+
+    assert_eq!(message.stop_reason, Some(StopReason::EndTurn));
+    assert_eq!(message.content.len(), 3);
+    assert_eq!(extract_text(&message.content), "Rust 2024 is out");
+    assert_eq!(message.usage.input_tokens, 30);
+
+    // The whole paused turn is resent as a single trailing assistant message
+    let requests = server.requests_to("POST", "/v1/messages");
+    assert_eq!(requests.len(), 3);
+    let body = requests[2].json();
+    assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+    assert_eq!(body["messages"][1]["role"], "assistant");
+    assert_eq!(body["messages"][1]["content"][1]["id"], "srvtoolu_2");
+
+    // The limit stops continuation while the turn is still paused
+    let message = client.messages().auto_continue(1).create(params).await.unwrap();
+    assert_eq!(message.stop_reason, Some(StopReason::PauseTurn));
+    assert_eq!(message.content.len(), 2);
+}
+
+#[cfg(feature = "schemars")]
+#[tokio::test]
+async fn test_create_structured_retries_invalid_output() {
+    /// Contact details found in the text.
+    #[derive(Debug, PartialEq, serde::Deserialize, anthropic_sdk::schemars::JsonSchema)]
+    #[schemars(crate = "anthropic_sdk::schemars")]
+    struct Contact {
+        name: String,
+        age: u32,
+    }
+
+    let server = MockServer::start().await.unwrap();
+    server
+        .enqueue_message(MockResponse::message(fixtures::tool_use_message("Contact", json!({"name": "Jane"}))))
+        .enqueue_message(MockResponse::message(fixtures::tool_use_message("Contact", json!({"name": "Jane", "age": 41}))));
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Jane is 41").build();
+    let contact: Contact = client.messages().create_structured(params.clone(), 1).await.unwrap();
+    assert_eq!(contact, Contact { name: "Jane".to_string(), age: 41 });
+
+    let requests = server.requests_to("POST", "/v1/messages");
+    let first = requests[0].json();
+    assert_eq!(first["tool_choice"], json!({"type": "tool", "name": "Contact"}));
+    assert_eq!(first["tools"][0]["input_schema"]["required"], json!(["name", "age"]));
+    let retry = requests[1].json();
+    let feedback = &retry["messages"][2]["content"][0];
+    assert_eq!(feedback["type"], "tool_result");
+    assert_eq!(feedback["is_error"], true);
+    assert!(feedback["content"].as_str().unwrap().contains("/age: required property is missing"));
+
+    // Out of retries
+    server.enqueue_message(MockResponse::text("I'd rather not"));
+    let error = client.messages().create_structured::<Contact>(params, 0).await.unwrap_err();
+    assert!(matches!(error, AnthropicError::StructuredOutput { attempts: 1, .. }), "{:?}", error);
+}
+
+#[tokio::test]
+async fn test_streaming_tolerates_unknown_events() {
+    let server = MockServer::start().await.unwrap();
+    let start = fixtures::message(Vec::new(), StopReason::EndTurn);
+    server.enqueue_message(MockResponse::sse(vec![
+        ("message_start", json!({"type": "message_start", "message": start})),
+        ("ping", json!({"type": "ping"})),
+        ("content_block_start", json!({"type": "content_block_start", "index": 0,
+            "content_block": {"type": "hologram", "frames": []}})),
+        ("content_block_delta", json!({"type": "content_block_delta", "index": 0,
+            "delta": {"type": "frame_delta", "frame": 1}})),
+        ("content_block_stop", json!({"type": "content_block_stop", "index": 0})),
+        ("content_block_start", json!({"type": "content_block_start", "index": 1,
+            "content_block": {"type": "text", "text": ""}})),
+        ("content_block_delta", json!({"type": "content_block_delta", "index": 1,
+            "delta": {"type": "text_delta", "text": "Still here"}})),
+        ("content_block_stop", json!({"type": "content_block_stop", "index": 1})),
+        ("telemetry", json!({"type": "telemetry", "latency_ms": 12})),
+        ("message_delta", json!({"type": "message_delta",
+            "delta": {"stop_reason": "brand_new_reason", "stop_sequence": null},
+            "usage": {"output_tokens": 2}})),
+        ("message_stop", json!({"type": "message_stop"})),
+    ]));
+
+    let client = server.client().unwrap();
+    let stream = client
+        .messages()
+        .create_stream(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").stream(true).build())
+        .await
+        .unwrap();
+
+    let message = stream.final_message().await.unwrap();
+    assert_eq!(message.content[0], ContentBlock::Unknown(json!({"type": "hologram", "frames": []})));
+    assert_eq!(extract_text(&message.content), "Still here");
+    assert_eq!(message.stop_reason, Some(StopReason::Other("brand_new_reason".to_string())));
+}
+
+#[tokio::test]
+async fn test_streaming_error_event() {
+    let server = MockServer::start().await.unwrap();
+    let start = fixtures::message(Vec::new(), StopReason::EndTurn);
+    server.enqueue_message(MockResponse::sse(vec![
+        ("message_start", json!({"type": "message_start", "message": start})),
+        ("error", json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}})),
+    ]));
+
+    let client = server.client().unwrap();
+    let stream = client
+        .messages()
+        .create_stream(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").stream(true).build())
+        .await
+        .unwrap();
+
+    let error = stream.final_message().await.unwrap_err();
+    assert!(error.to_string().contains("overloaded_error: Overloaded"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_streaming_error_mentioning_ping_is_not_dropped() {
+    let server = MockServer::start().await.unwrap();
+    let start = fixtures::message(Vec::new(), StopReason::EndTurn);
+    server.enqueue_message(MockResponse::sse(vec![
+        ("message_start", json!({"type": "message_start", "message": start})),
+        ("ping", json!({"type": "ping"})),
+        ("error", json!({"type": "error", "error": {"type": "api_error", "message": "Upstream ping timed out"}})),
+    ]));
+
+    let client = server.client().unwrap();
+    let stream = client
+        .messages()
+        .create_stream(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").stream(true).build())
+        .await
+        .unwrap();
+
+    let error = stream.final_message().await.unwrap_err();
+    assert!(error.to_string().contains("api_error: Upstream ping timed out"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_streaming_thinking() {
+    let server = MockServer::start().await.unwrap();
+    let reply = fixtures::message(
+        vec![
+            ContentBlock::Thinking {
+                thinking: "Two plus two is four".to_string(),
+                signature: "sig_math".to_string(),
+            },
+            ContentBlock::Text { text: "4".to_string(), citations: None },
+        ],
+        StopReason::EndTurn,
+    );
+    server.enqueue_message(MockResponse::stream_message(reply.clone()));
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-sonnet-4-20250514", 2048)
+        .user("What is 2 + 2?")
+        .enable_thinking(1024)
+        .stream(true)
+        .build();
+    let message = client.messages().create_stream(params).await.unwrap().final_message().await.unwrap();
+    assert_eq!(message.content, reply.content);
+
+    let body = server.last_request().unwrap().json();
+    assert_eq!(body["thinking"]["budget_tokens"], 1024);
+}
+
+#[tokio::test]
+async fn test_count_tokens() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue(
+        "POST",
+        "/v1/messages/count_tokens",
+        MockResponse::json(serde_json::json!({"input_tokens": 2_000_000})),
+    );
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+        .system("You are a helpful assistant.")
+        .user("How many tokens is this?")
+        .enable_thinking(512)
+        .build();
+    let count = client.messages().count_tokens(&params).await.unwrap();
+    assert_eq!(count.input_tokens, 2_000_000);
+
+    let pricing = client.models().get_pricing("claude-3-5-sonnet-20241022").await.unwrap();
+    assert_eq!(count.input_cost(&pricing), 2.0 * pricing.input_price_per_million);
+
+    let body = server.requests_to("POST", "/v1/messages/count_tokens").pop().unwrap().json();
+    assert_eq!(body["system"], "You are a helpful assistant.");
+    assert_eq!(body["thinking"]["type"], "enabled");
+    assert!(body.get("max_tokens").is_none());
+}
+
+#[tokio::test]
+async fn test_request_parameters_and_service_tier() {
+    let server = MockServer::start().await.unwrap();
+    let mut reply = fixtures::text_message("Hi");
+    reply.usage.service_tier = Some(ServiceTier::Priority);
+    server.enqueue_message(MockResponse::message(reply));
+
+    let client = server.client().unwrap();
+    let message = client
+        .messages()
+        .create(
+            MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100)
+                .user("Hi")
+                .service_tier(ServiceTierPreference::Auto)
+                .mcp_server(McpServer::url("docs", "https://mcp.example.com/sse"))
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(message.usage.service_tier, Some(ServiceTier::Priority));
+
+    let request = server.last_request().unwrap();
+    assert_eq!(request.json()["service_tier"], "auto");
+    assert_eq!(request.json()["mcp_servers"][0]["name"], "docs");
+    assert_eq!(request.header("anthropic-beta"), Some(anthropic_sdk::types::MCP_CLIENT_BETA));
+
+    // The client-bound builder forwards the same options
+    server.enqueue_message(MockResponse::text("Hi again"));
+    client
+        .messages()
+        .create_with_builder("claude-3-5-sonnet-latest", 100)
+        .user("Hi")
+        .user_id("user-123")
+        .service_tier(ServiceTierPreference::StandardOnly)
+        .container("container_abc")
+        .mcp_server(McpServer::url("docs", "https://mcp.example.com/sse"))
+        .send()
+        .await
+        .unwrap();
+
+    let body = server.last_request().unwrap().json();
+    assert_eq!(body["metadata"]["user_id"], "user-123");
+    assert_eq!(body["service_tier"], "standard_only");
+    assert_eq!(body["container"], "container_abc");
+    assert_eq!(body["mcp_servers"][0]["name"], "docs");
+}
+
+#[tokio::test]
+async fn test_mid_stream_disconnect() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue_message(MockResponse::text_stream("this stream never finishes").disconnect_after(3));
+
+    let client = server.client().unwrap();
+    let stream = client
+        .messages()
+        .create_stream(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").stream(true).build())
+        .await
+        .unwrap();
+
+    assert!(stream.final_message().await.is_err());
+}
+
+#[tokio::test]
+async fn test_rate_limit_and_overload_errors() {
+    let server = MockServer::start().await.unwrap();
+    server
+        .enqueue_message(MockResponse::rate_limited())
+        .enqueue_message(MockResponse::overloaded())
+        .enqueue_message(MockResponse::error(400, "invalid_request_error", "max_tokens: too large"));
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").build();
+
+    let error = client.messages().create(params.clone()).await.unwrap_err();
+    assert!(matches!(error, AnthropicError::RateLimit { .. }));
+
+    let error = client.messages().create(params.clone()).await.unwrap_err();
+    assert_eq!(error.status_code(), Some(529));
+
+    let error = client.messages().create(params).await.unwrap_err();
+    match error {
+        AnthropicError::BadRequest { message, .. } => assert!(message.contains("max_tokens")),
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_timeout() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue_message(MockResponse::text("too late").with_delay(Duration::from_secs(5)));
+
+    let config = server.config().with_timeout(Duration::from_millis(200));
+    let client = Anthropic::with_config(config).unwrap();
+
+    let result = client
+        .messages()
+        .create(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Hi").build())
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_tool_use_response() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue_message(MockResponse::message(fixtures::tool_use_message(
+        "get_weather",
+        json!({ "location": "Paris" }),
+    )));
+
+    let client = server.client().unwrap();
+    let message = client
+        .messages()
+        .create(MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Weather?").build())
+        .await
+        .unwrap();
+
+    match &message.content[0] {
+        ContentBlock::ToolUse { name, input, .. } => {
+            assert_eq!(name, "get_weather");
+            assert_eq!(input["location"], "Paris");
+        }
+        other => panic!("unexpected block: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_batches_lifecycle() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let params = BatchCreateParams::new(vec![
+        BatchRequest::new("first", "claude-3-5-sonnet-latest", 100).user("One").build(),
+        BatchRequest::new("second", "claude-3-5-sonnet-latest", 100).user("Two").build(),
+    ]);
+    let batch = client.batches().create(params).await.unwrap();
+    assert_eq!(batch.request_counts.total, 2);
+    assert_eq!(batch.processing_status, BatchStatus::InProgress);
+
+    let fetched = client.batches().get(&batch.id).await.unwrap();
+    assert_eq!(fetched.id, batch.id);
+
+    let list = client.batches().list(None).await.unwrap();
+    assert_eq!(list.data.len(), 1);
+
+    let results = vec![BatchResult {
+        custom_id: "first".to_string(),
+        response: BatchResponse {
+            status_code: 200,
+            headers: Default::default(),
+            body: BatchResponseBody::Success(fixtures::text_message("Batch reply")),
+        },
+    }];
+    server.complete_batch(&batch.id, results).unwrap();
+
+    let results = client.batches().get_results(&batch.id).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].custom_id, "first");
+
+    let request = server.requests_to("POST", "/v1/messages/batches").pop().unwrap();
+    assert_eq!(request.json()["requests"][1]["custom_id"], "second");
+}
+
+#[tokio::test]
+async fn test_batch_cancel() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let batch = client
+        .batches()
+        .create(BatchCreateParams::new(vec![
+            BatchRequest::new("only", "claude-3-5-sonnet-latest", 10).user("Hi").build(),
+        ]))
+        .await
+        .unwrap();
+
+    let cancelled = client.batches().cancel(&batch.id).await.unwrap();
+    assert_eq!(cancelled.processing_status, BatchStatus::Cancelling);
+}
+
+#[tokio::test]
+async fn test_uploaded_file_in_message() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let upload = FileUploadParams::new(b"%PDF-1.4".to_vec(), "report.pdf", "application/pdf", FilePurpose::Document);
+    let file = client.files().upload(upload).await.unwrap();
+
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 1024)
+        .user(vec![
+            ContentBlockParam::from_file_object(&file).unwrap(),
+            ContentBlockParam::text("Summarize this report"),
+        ])
+        .build();
+    client.messages().create(params.clone()).await.unwrap();
+    client.messages().create_stream(params).await.unwrap().final_message().await.unwrap();
+
+    for request in server.requests_to("POST", "/v1/messages") {
+        assert_eq!(request.header("anthropic-beta"), Some("files-api-2025-04-14"));
+        assert_eq!(request.json()["messages"][0]["content"][0]["source"]["file_id"], file.id.as_str());
+    }
+}
+
+#[tokio::test]
+async fn test_files_lifecycle() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let upload = FileUploadParams::new(b"hello file".to_vec(), "notes.txt", "text/plain", FilePurpose::Document);
+    let file = client.files().upload(upload).await.unwrap();
+    assert_eq!(file.filename, "notes.txt");
+    assert_eq!(file.size_bytes, 10);
+    assert_eq!(file.purpose, FilePurpose::Document);
+
+    let fetched = client.files().get(&file.id).await.unwrap();
+    assert_eq!(fetched.id, file.id);
+
+    let download = client.files().download(&file.id).await.unwrap();
+    assert_eq!(download.content, b"hello file");
+    assert_eq!(download.filename, "notes.txt");
+
+    let list = client.files().list(None).await.unwrap();
+    assert_eq!(list.data.len(), 1);
+
+    client.files().delete(&file.id).await.unwrap();
+    assert!(matches!(
+        client.files().get(&file.id).await.unwrap_err(),
+        AnthropicError::NotFound { .. }
+    ));
+
+    let upload_request = server.requests_to("POST", "/v1/files").pop().unwrap();
+    assert!(upload_request
+        .header("content-type")
+        .unwrap()
+        .starts_with("multipart/form-data"));
+}
+
+#[tokio::test]
+async fn test_files_binary_upload_round_trips() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let bytes = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0xff, 0xfe, 0x80];
+    let upload = FileUploadParams::new(bytes.clone(), "image.png", "image/png", FilePurpose::Vision);
+    let file = client.files().upload(upload).await.unwrap();
+    assert_eq!(file.size_bytes, bytes.len() as u64);
+
+    let download = client.files().download(&file.id).await.unwrap();
+    assert_eq!(download.content, bytes);
+}
+
+#[tokio::test]
+async fn test_models() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+
+    let models = client.models().list(None).await.unwrap();
+    assert!(!models.data.is_empty());
+
+    let model = client.models().get(&models.data[0].id).await.unwrap();
+    assert_eq!(model.id, models.data[0].id);
+
+    server.enqueue("GET", "/v1/models/*", MockResponse::json(fixtures::model("claude-custom", "Custom")));
+    let model = client.models().get("claude-custom").await.unwrap();
+    assert_eq!(model.display_name, "Custom");
+}
+
+#[tokio::test]
+async fn test_conversation_records_history() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+    let mut conversation = Conversation::new("claude-3-5-sonnet-latest", 200).system("Be brief.");
+
+    server.enqueue_message(MockResponse::text("Paris."));
+    let reply = conversation.send(&client, "Capital of France?").await.unwrap();
+    assert_eq!(reply.text(), "Paris.");
+
+    server.enqueue_message(MockResponse::text_stream("About two million."));
+    let mut streamed = String::new();
+    let reply = conversation
+        .stream(&client, "Population?", |event| {
+            if let MessageStreamEvent::ContentBlockDelta { delta: ContentBlockDelta::TextDelta { text }, .. } = event {
+                streamed.push_str(text);
+            }
+        })
+        .await
+        .unwrap();
+    assert_eq!(reply.text(), "About two million.");
+    assert_eq!(streamed, "About two million.");
+
+    // The second request carried the first exchange
+    let body = server.requests_to("POST", "/v1/messages")[1].json();
+    assert_eq!(body["system"], "Be brief.");
+    assert_eq!(body["messages"].as_array().unwrap().len(), 3);
+    assert_eq!(body["messages"][1]["role"], "assistant");
+    assert_eq!(body["messages"][1]["content"][0]["text"], "Paris.");
+
+    // Failed turns leave the history untouched
+    server.enqueue_message(MockResponse::error(400, "invalid_request_error", "bad request"));
+    assert!(conversation.send(&client, "Ignored").await.is_err());
+    assert_eq!(conversation.messages().len(), 4);
+
+    let restored = Conversation::from_json(&conversation.to_json().unwrap()).unwrap();
+    assert_eq!(restored.messages().len(), 4);
+    assert_eq!(restored.messages()[3].role, Role::Assistant);
+}
+
+#[tokio::test]
+async fn test_conversation_history_policy() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+    let mut conversation = Conversation::new("claude-3-5-sonnet-latest", 200)
+        .history_policy(HistoryPolicy::new(150).strategy(DropOldestTurns::new()));
+
+    for turn in 0..4 {
+        server.enqueue_message(MockResponse::text("x".repeat(1000)));
+        conversation.send(&client, format!("Turn {}", turn)).await.unwrap();
+    }
+
+    // Older turns were dropped before each request, keeping the latest question
+    let body = server.requests_to("POST", "/v1/messages").pop().unwrap().json();
+    assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(body["messages"][0]["content"], "Turn 3");
+    assert_eq!(conversation.messages().len(), 2);
+}
+
+fn truncated(text: &str) -> MockResponse {
+    MockResponse::message(fixtures::message(
+        vec![ContentBlock::Text { text: text.to_string(), citations: None }],
+        StopReason::MaxTokens,
+    ))
+}
+
+#[tokio::test]
+async fn test_create_until_complete() {
+    let server = MockServer::start().await.unwrap();
+    server
+        .enqueue_message(truncated("The quick brown "))
+        .enqueue_message(truncated(" fox jumps"))
+        .enqueue_message(MockResponse::text(" over."));
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Finish the sentence").build();
+    let message = client
+        .messages()
+        .create_until_complete(params, ContinuationLimits::new(5))
+        .await
+        .unwrap();
+
+    assert_eq!(message.content.len(), 1);
+    assert_eq!(message.text(), "The quick brown fox jumps over.");
+    assert_eq!(message.stop_reason, Some(StopReason::EndTurn));
+    assert_eq!(message.usage.input_tokens, 30);
+    assert_eq!(message.usage.output_tokens, 6);
+
+    // Each continuation replaces the prefill rather than stacking assistant turns
+    let requests = server.requests_to("POST", "/v1/messages");
+    assert_eq!(requests.len(), 3);
+    let second = requests[1].json();
+    assert_eq!(second["messages"].as_array().unwrap().len(), 2);
+    assert_eq!(second["messages"][1]["role"], "assistant");
+    assert_eq!(second["messages"][1]["content"][0]["text"], "The quick brown");
+    let third = requests[2].json();
+    assert_eq!(third["messages"].as_array().unwrap().len(), 2);
+    assert_eq!(third["messages"][1]["content"][0]["text"], "The quick brown fox jumps");
+}
+
+#[tokio::test]
+async fn test_create_until_complete_respects_limits() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Go on").build();
+
+    server.enqueue_message(truncated("one two")).enqueue_message(truncated(" three four"));
+    let message = client
+        .messages()
+        .create_until_complete(params.clone(), ContinuationLimits::new(1))
+        .await
+        .unwrap();
+    assert_eq!(message.text(), "one two three four");
+    assert_eq!(message.stop_reason, Some(StopReason::MaxTokens));
+    assert_eq!(server.requests_to("POST", "/v1/messages").len(), 2);
+
+    // The output budget caps the continuation request and then stops it
+    server.reset();
+    server.enqueue_message(truncated("one two three")).enqueue_message(truncated(" four five"));
+    let message = client
+        .messages()
+        .create_until_complete(params, ContinuationLimits::new(5).max_output_tokens(5))
+        .await
+        .unwrap();
+    assert_eq!(message.usage.output_tokens, 5);
+    let requests = server.requests_to("POST", "/v1/messages");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].json()["max_tokens"], 2);
+}
+
+#[tokio::test]
+async fn test_create_until_complete_keeps_trailing_whitespace() {
+    let server = MockServer::start().await.unwrap();
+    let tool_use = ContentBlock::ToolUse {
+        id: "toolu_1".to_string(),
+        name: "lookup".to_string(),
+        input: json!({}),
+    };
+    server
+        .enqueue_message(truncated("Let me check. "))
+        .enqueue_message(MockResponse::message(fixtures::message(vec![tool_use.clone()], StopReason::ToolUse)));
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Look it up").build();
+    let message = client
+        .messages()
+        .create_until_complete(params, ContinuationLimits::default())
+        .await
+        .unwrap();
+
+    // Only the prefill that was sent is trimmed
+    let requests = server.requests_to("POST", "/v1/messages");
+    assert_eq!(requests[1].json()["messages"][1]["content"][0]["text"], "Let me check.");
+    assert_eq!(
+        message.content,
+        vec![ContentBlock::Text { text: "Let me check. ".to_string(), citations: None }, tool_use]
+    );
+}
+
+#[tokio::test]
+async fn test_stream_until_complete() {
+    let server = MockServer::start().await.unwrap();
+    server
+        .enqueue_message(MockResponse::stream_message(fixtures::message(
+            vec![ContentBlock::Text { text: "Once upon ".to_string(), citations: None }],
+            StopReason::MaxTokens,
+        )))
+        .enqueue_message(MockResponse::text_stream(" a time."));
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-5-sonnet-latest", 100).user("Tell a story").build();
+    let mut starts = 0;
+    let message = client
+        .messages()
+        .stream_until_complete(params, ContinuationLimits::default(), |event| {
+            if let MessageStreamEvent::MessageStart { .. } = event {
+                starts += 1;
+            }
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(starts, 2);
+    assert_eq!(message.text(), "Once upon a time.");
+    assert_eq!(message.stop_reason, Some(StopReason::EndTurn));
+    let second = server.requests_to("POST", "/v1/messages")[1].json();
+    assert_eq!(second["messages"][1]["content"][0]["text"], "Once upon");
+}
+
+#[tokio::test]
+async fn test_create_until_complete_does_not_prefill_with_thinking() {
+    let server = MockServer::start().await.unwrap();
+    server.enqueue_message(MockResponse::message(fixtures::message(
+        vec![
+            ContentBlock::Thinking { thinking: "Plan the essay".to_string(), signature: "sig".to_string() },
+            ContentBlock::Text { text: "The essay begins ".to_string(), citations: None },
+        ],
+        StopReason::MaxTokens,
+    )));
+
+    let client = server.client().unwrap();
+    let params = MessageCreateBuilder::new("claude-3-7-sonnet-latest", 2048)
+        .user("Write an essay")
+        .enable_thinking(1024)
+        .build();
+    let message = client
+        .messages()
+        .create_until_complete(params, ContinuationLimits::new(3))
+        .await
+        .unwrap();
+
+    // The truncated response comes back untouched instead of a rejected prefill request
+    assert_eq!(message.stop_reason, Some(StopReason::MaxTokens));
+    assert_eq!(message.text(), "The essay begins ");
+    assert_eq!(server.requests_to("POST", "/v1/messages").len(), 1);
+}
+
+#[tokio::test]
+async fn test_conversation_history_policy_failed_turn_keeps_history() {
+    let server = MockServer::start().await.unwrap();
+    let client = server.client().unwrap();
+    let mut conversation = Conversation::new("claude-3-5-sonnet-latest", 200)
+        .history_policy(HistoryPolicy::new(150).strategy(DropOldestTurns::new()));
+    for turn in 0..3 {
+        conversation.push(MessageParam { role: Role::User, content: format!("Turn {}", turn).into() });
+        conversation.push(MessageParam { role: Role::Assistant, content: "x".repeat(1000).into() });
+    }
+
+    // The policy trimmed the request, but the failure leaves the stored history as it was
+    server.enqueue_message(MockResponse::error(400, "invalid_request_error", "bad request"));
+    assert!(conversation.send(&client, "Turn 3").await.is_err());
+    let body = server.requests_to("POST", "/v1/messages").pop().unwrap().json();
+    assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(conversation.messages().len(), 6);
+    assert_eq!(conversation.messages()[0].content, MessageContent::Text("Turn 0".to_string()));
+
+    // Once a turn succeeds, the trimmed history is kept
+    server.enqueue_message(MockResponse::text("Done."));
+    conversation.send(&client, "Turn 3").await.unwrap();
+    assert_eq!(conversation.messages().len(), 2);
+    assert_eq!(conversation.messages()[0].content, MessageContent::Text("Turn 3".to_string()));
+}
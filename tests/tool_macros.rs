@@ -0,0 +1,170 @@
+use anthropic_sdk::schemars::JsonSchema;
+use anthropic_sdk::tools::{tool, DefinedTool, ToolInput, ToolRegistry};
+use anthropic_sdk::types::{ToolResultContent, ToolUse};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Get the current weather for a city.
+#[derive(Deserialize, ToolInput)]
+#[allow(dead_code)]
+struct GetWeatherInput {
+    /// City name, e.g. "Paris"
+    city: String,
+    /// Temperature unit
+    unit: Option<Unit>,
+    #[serde(rename = "days", default)]
+    forecast_days: u32,
+    #[serde(skip)]
+    cache_key: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[schemars(crate = "anthropic_sdk::schemars")]
+#[serde(rename_all = "lowercase")]
+#[allow(dead_code)]
+enum Unit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Deserialize, ToolInput)]
+#[tool(name = "search_docs", description = "Search the documentation")]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct Search {
+    search_query: String,
+}
+
+/// Add two numbers.
+///
+/// # Arguments
+/// * `a` - First operand
+/// * `b` - Second operand
+#[tool]
+async fn add(a: i64, b: i64) -> String {
+    (a + b).to_string()
+}
+
+/// Divide two numbers.
+#[tool]
+fn divide(a: f64, b: f64) -> Result<serde_json::Value, String> {
+    if b == 0.0 {
+        return Err("division by zero".to_string());
+    }
+    Ok(json!({ "quotient": a / b }))
+}
+
+#[test]
+fn test_derive_tool_input() {
+    let tool = GetWeatherInput::tool();
+    assert_eq!(tool.name, "get_weather");
+    assert_eq!(tool.description, "Get the current weather for a city.");
+
+    let properties = &tool.input_schema.properties;
+    assert_eq!(properties["city"]["type"], "string");
+    assert_eq!(properties["city"]["description"], "City name, e.g. \"Paris\"");
+    assert_eq!(properties["unit"]["description"], "Temperature unit");
+    assert!(properties.contains_key("days"));
+    assert!(!properties.contains_key("forecast_days"));
+    assert!(!properties.contains_key("cache_key"));
+    assert_eq!(tool.input_schema.required, vec!["city".to_string()]);
+
+    // The enum is shared through $defs
+    let defs = tool.input_schema.additional["$defs"].as_object().unwrap();
+    assert_eq!(defs["Unit"]["enum"], json!(["celsius", "fahrenheit"]));
+
+    let input: GetWeatherInput = serde_json::from_value(json!({"city": "Paris", "unit": "celsius"})).unwrap();
+    assert_eq!(input.city, "Paris");
+    assert!(tool.validate_input(&json!({"city": "Paris", "days": 3})).is_ok());
+}
+
+#[test]
+fn test_derive_tool_input_overrides() {
+    let tool = Search::tool();
+    assert_eq!(tool.name, "search_docs");
+    assert_eq!(tool.description, "Search the documentation");
+    assert!(tool.input_schema.properties.contains_key("searchQuery"));
+    assert_eq!(tool.input_schema.required, vec!["searchQuery".to_string()]);
+}
+
+#[test]
+fn test_tool_attribute_definition() {
+    let tool = AddTool.definition();
+    assert_eq!(tool.name, "add");
+    assert_eq!(tool.description, "Add two numbers.");
+    assert_eq!(tool.input_schema.properties["a"]["type"], "integer");
+    assert_eq!(tool.input_schema.properties["b"]["description"], "Second operand");
+    assert_eq!(tool.input_schema.required, vec!["a".to_string(), "b".to_string()]);
+}
+
+#[tokio::test]
+async fn test_tool_attribute_executes() {
+    let mut registry = ToolRegistry::new();
+    registry.register_tool(AddTool).unwrap();
+    registry.register_tool(DivideTool).unwrap();
+    assert!(registry.has_tool("add"));
+    assert!(registry.has_tool("divide"));
+
+    let result = registry
+        .execute(&ToolUse {
+            id: "toolu_1".to_string(),
+            name: "add".to_string(),
+            input: json!({"a": 2, "b": 3}),
+        })
+        .await
+        .unwrap();
+    assert_eq!(result.tool_use_id, "toolu_1");
+    assert!(matches!(result.content, ToolResultContent::Text(ref text) if text == "5"));
+
+    let result = registry
+        .execute(&ToolUse {
+            id: "toolu_2".to_string(),
+            name: "divide".to_string(),
+            input: json!({"a": 1.0, "b": 4.0}),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(result.content, ToolResultContent::Json(ref value) if value["quotient"] == 0.25));
+
+    // Errors returned by the function surface as execution failures
+    let failed = registry
+        .execute(&ToolUse {
+            id: "toolu_3".to_string(),
+            name: "divide".to_string(),
+            input: json!({"a": 1.0, "b": 0.0}),
+        })
+        .await;
+    assert!(failed.unwrap_err().to_string().contains("division by zero"));
+
+    // The generated input schema rejects mistyped arguments before the call
+    let invalid = registry
+        .execute(&ToolUse {
+            id: "toolu_4".to_string(),
+            name: "add".to_string(),
+            input: json!({"a": "two", "b": 3}),
+        })
+        .await
+        .unwrap();
+    assert_eq!(invalid.is_error, Some(true));
+}
+
+#[tokio::test]
+async fn test_register_typed_with_derived_input() {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register_typed(|input: GetWeatherInput| async move {
+            Ok::<_, String>(json!({"city": input.city, "days": input.forecast_days}))
+        })
+        .unwrap();
+    assert_eq!(registry.get_tool_definition("get_weather").unwrap().input_schema.required, vec!["city".to_string()]);
+
+    let result = registry
+        .execute(&ToolUse {
+            id: "toolu_1".to_string(),
+            name: "get_weather".to_string(),
+            input: json!({"city": "Oslo", "days": 2}),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(result.content, ToolResultContent::Json(ref value) if *value == json!({"city": "Oslo", "days": 2})));
+}